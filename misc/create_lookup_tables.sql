@@ -0,0 +1,37 @@
+-- lookup tables for PostgreSQL, the names are the same as the *_type_name columns
+
+CREATE TABLE IF NOT EXISTS aircraft_types (
+    id                  SMALLINT PRIMARY KEY,
+    name                TEXT NOT NULL,
+    description         TEXT
+);
+INSERT INTO aircraft_types (id, name, description) VALUES
+    (0, 'unknown', 'unknown'),
+    (1, 'glider', 'glider or motor glider'),
+    (2, 'tow_plane', 'tow plane or tug plane'),
+    (3, 'helicopter', 'helicopter or rotorcraft'),
+    (4, 'skydiver', 'skydiver or parachute'),
+    (5, 'drop_plane', 'drop plane for skydivers'),
+    (6, 'hang_glider', 'hang glider (hard)'),
+    (7, 'paraglider', 'paraglider (soft)'),
+    (8, 'powered_aircraft', 'aircraft with reciprocating engine(s)'),
+    (9, 'jet_aircraft', 'aircraft with jet or turboprop engine(s)'),
+    (10, 'unknown', 'unknown'),
+    (11, 'balloon', 'balloon'),
+    (12, 'airship', 'airship'),
+    (13, 'uav', 'unmanned aerial vehicle'),
+    (14, 'reserved', 'reserved'),
+    (15, 'static_object', 'static obstacle')
+ON CONFLICT (id) DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS address_types (
+    id                  SMALLINT PRIMARY KEY,
+    name                TEXT NOT NULL,
+    description         TEXT
+);
+INSERT INTO address_types (id, name, description) VALUES
+    (0, 'random', 'random address'),
+    (1, 'icao', 'ICAO 24-bit aircraft address'),
+    (2, 'flarm', 'FLARM ID'),
+    (3, 'ogn', 'OGN tracker ID')
+ON CONFLICT (id) DO NOTHING;
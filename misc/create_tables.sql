@@ -1,4 +1,5 @@
 -- table creation script for PostgreSQL (with PostGIS extension)
+-- speeds, altitudes, climb and turn rates are in the unit system of column "units": aviation (kt, ft, ft/min, rot) or si (km/h, m, m/s, °/s)
 
 CREATE TABLE IF NOT EXISTS errors (
     "ts"                TIMESTAMPTZ NOT NULL,
@@ -19,11 +20,13 @@ CREATE TABLE IF NOT EXISTS server_comments (
 
 CREATE TABLE IF NOT EXISTS positions (
     "ts"                TIMESTAMPTZ NOT NULL,
+    units               TEXT NOT NULL,
 
     -- APRS message body
     src_call            VARCHAR(9) NOT NULL,
     dst_call            VARCHAR(9) NOT NULL,
     receiver            VARCHAR(9) NOT NULL,
+    protocol            TEXT NOT NULL,
 
     -- APRS position message
     receiver_time       VARCHAR(7) NOT NULL,
@@ -36,10 +39,12 @@ CREATE TABLE IF NOT EXISTS positions (
     altitude            INTEGER,
     address_type        SMALLINT,
     aircraft_type       SMALLINT,
+    address_type_name   TEXT,
+    aircraft_type_name  TEXT,
     is_stealth          BOOLEAN,
     is_notrack          BOOLEAN,
     address             INTEGER,
-    climb_rate          INTEGER,
+    climb_rate          DOUBLE PRECISION,
     turn_rate           DOUBLE PRECISION,
     error               SMALLINT,
     frequency_offset    DOUBLE PRECISION,
@@ -53,11 +58,23 @@ CREATE TABLE IF NOT EXISTS positions (
 
     unparsed            TEXT,
 
+    -- derived from the ICAO address (country of registration, N-number of US aircraft)
+    country             TEXT,
+    registration        TEXT,
+
     -- additional (externally calculated) fields
     receiver_ts         TIMESTAMPTZ,
     bearing             DOUBLE PRECISION,
     distance            DOUBLE PRECISION,
+    slant_range         DOUBLE PRECISION,
+    elevation_angle     DOUBLE PRECISION,
+    altitude_difference INTEGER,
     normalized_quality  DOUBLE PRECISION,
+    airfield            TEXT,
+    airfield_distance   DOUBLE PRECISION,
+    pressure_altitude   INTEGER,
+    qnh                 DOUBLE PRECISION,
+    altitude_estimated  BOOLEAN,
 
     -- additional (externally calculated) field, for PostGIS only
     location            GEOMETRY(POINT, 4326),
@@ -67,6 +84,40 @@ CREATE TABLE IF NOT EXISTS positions (
     plausibility        SMALLINT
 );
 CREATE INDEX idx_positions_src_call ON positions (src_call, ts);
+CREATE INDEX idx_positions_protocol ON positions (protocol, ts);
+
+CREATE TABLE IF NOT EXISTS weather (
+    "ts"                TIMESTAMPTZ NOT NULL,
+    units               TEXT NOT NULL,
+
+    -- APRS message body
+    src_call            VARCHAR(9) NOT NULL,
+    dst_call            VARCHAR(9) NOT NULL,
+    receiver            VARCHAR(9) NOT NULL,
+    protocol            TEXT NOT NULL,
+
+    -- APRS position message
+    receiver_time       VARCHAR(7),
+    location            GEOMETRY(POINT, 4326),
+
+    -- parsed APRS weather data (converted from mph, °F, hundredths of an inch and tenths of hPa)
+    altitude            INTEGER,
+    wind_direction      SMALLINT,
+    wind_speed          DOUBLE PRECISION,
+    gust                DOUBLE PRECISION,
+    temperature         DOUBLE PRECISION,
+    rainfall_1h         DOUBLE PRECISION,
+    rainfall_24h        DOUBLE PRECISION,
+    rainfall_midnight   DOUBLE PRECISION,
+    humidity            SMALLINT,
+    barometric_pressure DOUBLE PRECISION,
+
+    unparsed            TEXT,
+
+    -- additional (externally calculated) fields
+    receiver_ts         TIMESTAMPTZ
+);
+CREATE INDEX idx_weather_src_call ON weather (src_call, ts);
 
 CREATE TABLE IF NOT EXISTS statuses (
     "ts"                TIMESTAMPTZ NOT NULL,
@@ -75,6 +126,7 @@ CREATE TABLE IF NOT EXISTS statuses (
     src_call            VARCHAR(9) NOT NULL,
     dst_call            VARCHAR(9) NOT NULL,
     receiver            VARCHAR(9) NOT NULL,
+    protocol            TEXT NOT NULL,
 
 	-- APRS status message
     receiver_time       VARCHAR(7) NOT NULL,
@@ -107,4 +159,260 @@ CREATE TABLE IF NOT EXISTS statuses (
     -- additional (externally calculated) fields
     receiver_ts         TIMESTAMPTZ
 );
-CREATE INDEX idx_statuses_src_call ON statuses (src_call, ts);
\ No newline at end of file
+CREATE INDEX idx_statuses_src_call ON statuses (src_call, ts);
+
+CREATE TABLE IF NOT EXISTS timings (
+    "ts"                TIMESTAMPTZ NOT NULL,
+    receiver            VARCHAR(9) NOT NULL,
+    sample_count        INTEGER,
+
+    -- delay [s] between receiver time and ingest time of the positions
+    min_delay           DOUBLE PRECISION,
+    p10_delay           DOUBLE PRECISION,
+    median_delay        DOUBLE PRECISION,
+    p90_delay           DOUBLE PRECISION,
+
+    -- median delay relative to all receivers [s], bad clock if more than 2s
+    clock_offset        DOUBLE PRECISION,
+    bad_clock           BOOLEAN
+);
+CREATE INDEX idx_timings_receiver ON timings (receiver, ts);
+
+CREATE TABLE IF NOT EXISTS fused_positions (
+    "ts"                TIMESTAMPTZ NOT NULL,
+    units               TEXT NOT NULL,
+
+    -- APRS message body
+    src_call            VARCHAR(9) NOT NULL,
+    dst_call            VARCHAR(9) NOT NULL,
+    protocol            TEXT NOT NULL,
+
+    -- APRS position message
+    receiver_time       VARCHAR(7),
+    location            GEOMETRY(POINT, 4326),
+
+    -- parsed APRS position comment (from the first received copy)
+    course              SMALLINT,
+    speed               SMALLINT,
+    altitude            INTEGER,
+    climb_rate          DOUBLE PRECISION,
+    turn_rate           DOUBLE PRECISION,
+    address_type        SMALLINT,
+    aircraft_type       SMALLINT,
+    address_type_name   TEXT,
+    aircraft_type_name  TEXT,
+    address             INTEGER,
+
+    -- all receivers of this transmission (receiver, ts, bearing, distance, slant_range, elevation_angle, signal_quality, normalized_quality)
+    receiver_count      SMALLINT,
+    receivers           JSONB,
+
+    -- additional (externally calculated) fields
+    receiver_ts         TIMESTAMPTZ
+);
+CREATE INDEX idx_fused_positions_src_call ON fused_positions (src_call, ts);
+
+CREATE TABLE IF NOT EXISTS flights (
+    units               TEXT NOT NULL,
+
+    -- APRS message body
+    src_call            VARCHAR(9) NOT NULL,
+    dst_call            VARCHAR(9) NOT NULL,
+    protocol            TEXT NOT NULL,
+
+    -- parsed APRS position comment
+    address_type        SMALLINT,
+    aircraft_type       SMALLINT,
+    address_type_name   TEXT,
+    aircraft_type_name  TEXT,
+    address             INTEGER,
+
+    -- takeoff (unknown if the aircraft was first seen in the air)
+    takeoff_ts          TIMESTAMPTZ,
+    takeoff_location    GEOMETRY(POINT, 4326),
+    takeoff_airfield    TEXT,
+    takeoff_runway      VARCHAR(2),
+
+    -- landing (unknown if the aircraft disappeared in the air)
+    landing_ts          TIMESTAMPTZ,
+    landing_location    GEOMETRY(POINT, 4326),
+    landing_airfield    TEXT,
+    landing_runway      VARCHAR(2),
+
+    -- launch (gliders only: aerotow, winch, self_launch or unknown), release height above takeoff
+    launch_method       TEXT,
+    tow_plane           VARCHAR(9),
+    release_height      INTEGER,
+
+    -- flight summary
+    max_altitude        INTEGER,
+    duration            INTEGER
+);
+CREATE INDEX idx_flights_src_call ON flights (src_call, takeoff_ts);
+
+CREATE TABLE IF NOT EXISTS thermals (
+    units               TEXT NOT NULL,
+    src_call            VARCHAR(9) NOT NULL,
+
+    -- circling phase
+    start_ts            TIMESTAMPTZ NOT NULL,
+    end_ts              TIMESTAMPTZ NOT NULL,
+    duration            INTEGER,
+
+    -- centre of the thermal, altitudes and average climb rate
+    location            GEOMETRY(POINT, 4326),
+    base_altitude       INTEGER,
+    top_altitude        INTEGER,
+    climb_rate          DOUBLE PRECISION
+);
+CREATE INDEX idx_thermals_location ON thermals USING GIST (location);
+
+CREATE TABLE IF NOT EXISTS winds (
+    ts                  TIMESTAMPTZ NOT NULL,
+    units               TEXT NOT NULL,
+
+    -- centre of the region and lower bound of the altitude band
+    location            GEOMETRY(POINT, 4326),
+    altitude_band       INTEGER,
+
+    -- wind direction [°] (where the wind comes from), speed and gust [kt] or [km/h] (see units)
+    wind_direction      SMALLINT,
+    wind_speed          DOUBLE PRECISION,
+    gust                DOUBLE PRECISION,
+
+    -- number of estimates from circling aircraft and weather stations
+    circling_count      INTEGER,
+    station_count       INTEGER
+);
+CREATE INDEX idx_winds_ts ON winds (ts);
+
+CREATE TABLE IF NOT EXISTS qnh (
+    ts                  TIMESTAMPTZ NOT NULL,
+
+    -- centre of the region
+    location            GEOMETRY(POINT, 4326),
+
+    -- median QNH [hPa] of aircraft with GPS altitude and flight level
+    qnh                 DOUBLE PRECISION,
+    sample_count        INTEGER
+);
+CREATE INDEX idx_qnh_ts ON qnh (ts);
+
+-- one row per receiver and grid cell, upserted periodically
+CREATE TABLE IF NOT EXISTS coverage (
+    ts                  TIMESTAMPTZ NOT NULL,
+    units               TEXT NOT NULL,
+    receiver            VARCHAR(9) NOT NULL,
+
+    -- centre and size [°] of the grid cell
+    location            GEOMETRY(POINT, 4326),
+    resolution          DOUBLE PRECISION,
+
+    -- statistics of all positions received in this cell (quality_count: positions with signal quality)
+    message_count       INTEGER,
+    quality_count       INTEGER,
+    max_normalized_quality  DOUBLE PRECISION,
+    mean_normalized_quality DOUBLE PRECISION,
+    min_altitude        INTEGER,
+    max_distance        DOUBLE PRECISION
+);
+CREATE UNIQUE INDEX idx_coverage_cell ON coverage (receiver, location, resolution);
+
+-- one row per receiver, bearing sector and altitude band, upserted periodically
+CREATE TABLE IF NOT EXISTS polars (
+    ts                  TIMESTAMPTZ NOT NULL,
+    units               TEXT NOT NULL,
+    receiver            VARCHAR(9) NOT NULL,
+
+    -- centre of the bearing sector [°] and lower bound of the altitude band (relative to the receiver)
+    bearing             DOUBLE PRECISION,
+    altitude_band       INTEGER,
+
+    -- max. range and range reached by 90% of the positions [m]
+    message_count       INTEGER,
+    max_range           DOUBLE PRECISION,
+    percentile_range    DOUBLE PRECISION
+);
+CREATE UNIQUE INDEX idx_polars_sector ON polars (receiver, bearing, altitude_band);
+
+CREATE TABLE IF NOT EXISTS alerts (
+    ts                  TIMESTAMPTZ NOT NULL,
+    receiver            VARCHAR(9) NOT NULL,
+
+    -- violated rule (e.g. "cpu_temperature>75") or "beacon_gap", active (raised) or not (cleared)
+    rule                TEXT NOT NULL,
+    value               DOUBLE PRECISION,
+    active              BOOLEAN NOT NULL,
+    message             TEXT
+);
+CREATE INDEX idx_alerts_receiver ON alerts (receiver, ts);
+
+CREATE TABLE IF NOT EXISTS sensitivities (
+    ts                  TIMESTAMPTZ NOT NULL,
+
+    -- centre of the region (1°) and rank of the receiver within the region
+    location            GEOMETRY(POINT, 4326),
+    rank                INTEGER,
+    receiver            VARCHAR(9) NOT NULL,
+
+    -- mean normalized signal quality relative to the other receivers of the same transmissions [dB]
+    score               DOUBLE PRECISION,
+    comparison_count    INTEGER
+);
+CREATE INDEX idx_sensitivities_receiver ON sensitivities (receiver, ts);
+
+-- one row per receiver, upserted periodically
+CREATE TABLE IF NOT EXISTS receivers (
+    receiver            VARCHAR(9) PRIMARY KEY,
+    units               TEXT NOT NULL,
+
+    -- from the last position beacon
+    location            GEOMETRY(POINT, 4326),
+    altitude            INTEGER,
+
+    -- from the last status beacon
+    version             TEXT,
+    platform            TEXT,
+
+    first_seen          TIMESTAMPTZ NOT NULL,
+    last_seen           TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS receiver_versions (
+    "ts"                TIMESTAMPTZ NOT NULL,
+    receiver            VARCHAR(9) NOT NULL,
+
+    -- software version before and after the change
+    previous_version    TEXT NOT NULL,
+    version             TEXT NOT NULL,
+    platform            TEXT
+);
+CREATE INDEX idx_receiver_versions_receiver ON receiver_versions (receiver, ts);
+
+-- one row per device, upserted periodically (the statistics of every upsert are added)
+CREATE TABLE IF NOT EXISTS devices (
+    address             INTEGER NOT NULL,
+    address_type        SMALLINT NOT NULL,
+    address_type_name   TEXT,
+    aircraft_type       SMALLINT,
+    aircraft_type_name  TEXT,
+    src_call            VARCHAR(9),
+
+    -- latest firmware and hardware
+    software_version    DOUBLE PRECISION,
+    hardware_version    SMALLINT,
+    original_address    INTEGER,
+
+    first_seen          TIMESTAMPTZ NOT NULL,
+    last_seen           TIMESTAMPTZ NOT NULL,
+    message_count       INTEGER,
+    frequency_offset_count INTEGER,
+    mean_frequency_offset DOUBLE PRECISION,
+
+    -- all receivers the device was heard by
+    receiver_count      SMALLINT,
+    receivers           JSONB,
+
+    PRIMARY KEY (address_type, address)
+);
+CREATE INDEX idx_devices_software_version ON devices (software_version);
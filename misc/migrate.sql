@@ -0,0 +1,78 @@
+-- migration script for PostgreSQL: upgrades tables created with an older misc/create_tables.sql
+-- run misc/create_tables.sql first (it creates the new tables), then this script; every statement can be re-run
+
+-- protocol decoded from the destination callsign (existing rows get "unknown")
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS protocol TEXT NOT NULL DEFAULT 'unknown';
+ALTER TABLE positions ALTER COLUMN protocol DROP DEFAULT;
+CREATE INDEX IF NOT EXISTS idx_positions_protocol ON positions (protocol, ts);
+ALTER TABLE statuses ADD COLUMN IF NOT EXISTS protocol TEXT NOT NULL DEFAULT 'unknown';
+ALTER TABLE statuses ALTER COLUMN protocol DROP DEFAULT;
+ALTER TABLE weather ADD COLUMN IF NOT EXISTS protocol TEXT NOT NULL DEFAULT 'unknown';
+ALTER TABLE weather ALTER COLUMN protocol DROP DEFAULT;
+ALTER TABLE fused_positions ADD COLUMN IF NOT EXISTS protocol TEXT NOT NULL DEFAULT 'unknown';
+ALTER TABLE fused_positions ALTER COLUMN protocol DROP DEFAULT;
+ALTER TABLE flights ADD COLUMN IF NOT EXISTS protocol TEXT NOT NULL DEFAULT 'unknown';
+ALTER TABLE flights ALTER COLUMN protocol DROP DEFAULT;
+
+-- unit system of the row (existing rows were written in aviation units), climb rates are no longer rounded
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS units TEXT NOT NULL DEFAULT 'aviation';
+ALTER TABLE positions ALTER COLUMN units DROP DEFAULT;
+ALTER TABLE positions ALTER COLUMN climb_rate TYPE DOUBLE PRECISION;
+ALTER TABLE weather ADD COLUMN IF NOT EXISTS units TEXT NOT NULL DEFAULT 'aviation';
+ALTER TABLE weather ALTER COLUMN units DROP DEFAULT;
+ALTER TABLE fused_positions ADD COLUMN IF NOT EXISTS units TEXT NOT NULL DEFAULT 'aviation';
+ALTER TABLE fused_positions ALTER COLUMN units DROP DEFAULT;
+ALTER TABLE fused_positions ALTER COLUMN climb_rate TYPE DOUBLE PRECISION;
+ALTER TABLE flights ADD COLUMN IF NOT EXISTS units TEXT NOT NULL DEFAULT 'aviation';
+ALTER TABLE flights ALTER COLUMN units DROP DEFAULT;
+ALTER TABLE thermals ADD COLUMN IF NOT EXISTS units TEXT NOT NULL DEFAULT 'aviation';
+ALTER TABLE thermals ALTER COLUMN units DROP DEFAULT;
+ALTER TABLE winds ADD COLUMN IF NOT EXISTS units TEXT NOT NULL DEFAULT 'aviation';
+ALTER TABLE winds ALTER COLUMN units DROP DEFAULT;
+
+-- aircraft and address type names
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS address_type_name TEXT;
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS aircraft_type_name TEXT;
+ALTER TABLE fused_positions ADD COLUMN IF NOT EXISTS address_type_name TEXT;
+ALTER TABLE fused_positions ADD COLUMN IF NOT EXISTS aircraft_type_name TEXT;
+ALTER TABLE flights ADD COLUMN IF NOT EXISTS address_type_name TEXT;
+ALTER TABLE flights ADD COLUMN IF NOT EXISTS aircraft_type_name TEXT;
+
+-- country and N-number derived from the ICAO address
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS country TEXT;
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS registration TEXT;
+
+-- nearest airfield, QNH and receiver geometry
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS airfield TEXT;
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS airfield_distance DOUBLE PRECISION;
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS pressure_altitude INTEGER;
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS qnh DOUBLE PRECISION;
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS altitude_estimated BOOLEAN;
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS slant_range DOUBLE PRECISION;
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS elevation_angle DOUBLE PRECISION;
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS altitude_difference INTEGER;
+
+-- coverage: one row per receiver and grid cell (keep the latest row of every cell)
+DELETE FROM coverage a USING coverage b
+    WHERE a.receiver = b.receiver AND a.location = b.location AND a.resolution = b.resolution AND a.ts < b.ts;
+DROP INDEX IF EXISTS idx_coverage_receiver;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_coverage_cell ON coverage (receiver, location, resolution);
+-- coverage: number of positions with signal quality (weight of the mean), estimated for existing rows
+ALTER TABLE coverage ADD COLUMN IF NOT EXISTS quality_count INTEGER;
+UPDATE coverage SET quality_count = message_count
+    WHERE quality_count IS NULL AND mean_normalized_quality IS NOT NULL;
+
+-- polars: one row per receiver, bearing sector and altitude band (keep the latest row of every sector)
+DELETE FROM polars a USING polars b
+    WHERE a.receiver = b.receiver AND a.bearing = b.bearing AND a.altitude_band = b.altitude_band AND a.ts < b.ts;
+DROP INDEX IF EXISTS idx_polars_receiver;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_polars_sector ON polars (receiver, bearing, altitude_band);
+
+-- receiver_versions: the first status beacon after every start of the client is no version change
+DELETE FROM receiver_versions WHERE previous_version IS NULL;
+ALTER TABLE receiver_versions ALTER COLUMN previous_version SET NOT NULL;
+
+-- devices: number of positions with frequency offset (weight of the mean), estimated for existing rows
+ALTER TABLE devices ADD COLUMN IF NOT EXISTS frequency_offset_count INTEGER;
+UPDATE devices SET frequency_offset_count = message_count
+    WHERE frequency_offset_count IS NULL AND mean_frequency_offset IS NOT NULL;
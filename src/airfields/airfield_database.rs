@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use crate::utils::geo;
+
+// size of a grid cell of the spatial index [°]
+const CELL_SIZE: f64 = 0.2;
+
+#[derive(Debug, Clone)]
+pub struct Airfield {
+    pub code: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub elevation: Option<f64>, // [m]
+}
+
+#[derive(Debug, Default)]
+pub struct AirfieldDatabase {
+    pub airfields: Vec<Airfield>,
+
+    // spatial index: grid cell -> indices of the airfields in this cell
+    pub cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl AirfieldDatabase {
+    pub fn new() -> Self {
+        AirfieldDatabase::default()
+    }
+
+    // load an OurAirports CSV (*.csv) or a SeeYou waypoint file (*.cup)
+    pub fn load(&mut self, path: &str) -> Result<usize, String> {
+        let content =
+            std::fs::read_to_string(path).map_err(|err| format!("Can't read '{path}': {err}"))?;
+
+        let airfields = if path.to_lowercase().ends_with(".cup") {
+            super::cup::parse(&content)?
+        } else {
+            super::ourairports::parse(&content)?
+        };
+
+        let count = airfields.len();
+        for airfield in airfields {
+            self.insert(airfield);
+        }
+
+        Ok(count)
+    }
+
+    fn cell(latitude: f64, longitude: f64) -> (i32, i32) {
+        (
+            (latitude / CELL_SIZE).floor() as i32,
+            (longitude / CELL_SIZE).floor() as i32,
+        )
+    }
+
+    pub fn insert(&mut self, airfield: Airfield) {
+        let cell = Self::cell(airfield.latitude, airfield.longitude);
+        self.cells
+            .entry(cell)
+            .or_default()
+            .push(self.airfields.len());
+        self.airfields.push(airfield);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.airfields.is_empty()
+    }
+
+    // nearest airfield and its distance [m], only the grid cells within max_distance are searched
+    pub fn nearest(
+        &self,
+        latitude: f64,
+        longitude: f64,
+        max_distance: f64,
+    ) -> Option<(&Airfield, f64)> {
+        let (row, col) = Self::cell(latitude, longitude);
+
+        // the cells get narrower towards the poles, so more columns are needed there
+        let cell_height = geo::EARTH_RADIUS * CELL_SIZE.to_radians();
+        let cell_width = cell_height * latitude.to_radians().cos();
+        let max_cols = (180.0 / CELL_SIZE) as i32;
+        let rows = (max_distance / cell_height).ceil() as i32;
+        let cols = ((max_distance / cell_width).ceil() as i32).clamp(1, max_cols);
+
+        (row - rows..=row + rows)
+            .flat_map(|r| (col - cols..=col + cols).map(move |c| (r, c)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .map(|&index| {
+                let airfield = &self.airfields[index];
+                let distance =
+                    geo::distance(latitude, longitude, airfield.latitude, airfield.longitude);
+                (airfield, distance)
+            })
+            .filter(|(_, distance)| *distance <= max_distance)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+}
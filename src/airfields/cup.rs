@@ -0,0 +1,73 @@
+use super::airfield_database::Airfield;
+use crate::containers::units::FOOT;
+
+// waypoint styles which are airfields: 2 (grass), 4 (gliding) and 5 (solid)
+const AIRFIELD_STYLES: [&str; 3] = ["2", "4", "5"];
+
+// DDMM.mmmN / DDDMM.mmmE
+fn parse_coordinate(s: &str, degree_digits: usize) -> Option<f64> {
+    let (index, hemisphere) = s.char_indices().last()?;
+    let value = &s[..index];
+    let degrees = value.get(..degree_digits)?.parse::<f64>().ok()?;
+    let minutes = value.get(degree_digits..)?.parse::<f64>().ok()?;
+    let coordinate = degrees + minutes / 60.0;
+
+    match hemisphere {
+        'N' | 'E' => Some(coordinate),
+        'S' | 'W' => Some(-coordinate),
+        _ => None,
+    }
+}
+
+// 123.4m or 405ft
+fn parse_elevation(s: &str) -> Option<f64> {
+    if let Some(value) = s.strip_suffix("ft") {
+        value.parse::<f64>().ok().map(|value| value * FOOT)
+    } else {
+        s.strip_suffix('m').unwrap_or(s).parse::<f64>().ok()
+    }
+}
+
+// SeeYou waypoint file: name,code,country,lat,lon,elev,style,...
+pub fn parse(content: &str) -> Result<Vec<Airfield>, String> {
+    // the waypoints may be followed by a task section
+    let waypoints = content
+        .split("-----Related Tasks-----")
+        .next()
+        .unwrap_or_default();
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(waypoints.as_bytes());
+
+    let mut airfields = vec![];
+    for result in reader.records() {
+        let record = result.map_err(|err| format!("Invalid CUP record: {err}"))?;
+        if !AIRFIELD_STYLES.contains(&record.get(6).unwrap_or_default()) {
+            continue;
+        }
+
+        let (Some(latitude), Some(longitude)) = (
+            record.get(3).and_then(|s| parse_coordinate(s, 2)),
+            record.get(4).and_then(|s| parse_coordinate(s, 3)),
+        ) else {
+            return Err(format!("Invalid CUP coordinates: {record:?}"));
+        };
+
+        // waypoints without code are identified by their name
+        let code = match record.get(1).unwrap_or_default() {
+            "" => record.get(0).unwrap_or_default().to_string(),
+            code => code.to_string(),
+        };
+
+        airfields.push(Airfield {
+            code,
+            latitude,
+            longitude,
+            elevation: record.get(5).and_then(parse_elevation),
+        });
+    }
+
+    Ok(airfields)
+}
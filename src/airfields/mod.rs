@@ -0,0 +1,3 @@
+pub mod airfield_database;
+pub mod cup;
+pub mod ourairports;
@@ -0,0 +1,41 @@
+use serde::Deserialize;
+
+use super::airfield_database::Airfield;
+use crate::containers::units::FOOT;
+
+// the relevant columns of https://ourairports.com/data/airports.csv
+#[derive(Debug, Deserialize)]
+struct Record {
+    ident: String,
+    #[serde(rename = "type")]
+    airport_type: String,
+    latitude_deg: f64,
+    longitude_deg: f64,
+    elevation_ft: Option<f64>,
+}
+
+// heliports, seaplane bases, balloonports and closed airports are no takeoff/landing sites for us
+const AIRFIELD_TYPES: [&str; 3] = ["small_airport", "medium_airport", "large_airport"];
+
+pub fn parse(content: &str) -> Result<Vec<Airfield>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(content.as_bytes());
+
+    let mut airfields = vec![];
+    for result in reader.deserialize::<Record>() {
+        let record = result.map_err(|err| format!("Invalid OurAirports record: {err}"))?;
+        if !AIRFIELD_TYPES.contains(&record.airport_type.as_str()) {
+            continue;
+        }
+
+        airfields.push(Airfield {
+            code: record.ident,
+            latitude: record.latitude_deg,
+            longitude: record.longitude_deg,
+            elevation: record.elevation_ft.map(|elevation| elevation * FOOT),
+        });
+    }
+
+    Ok(airfields)
+}
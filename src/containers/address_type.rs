@@ -0,0 +1,32 @@
+use serde::Serialize;
+
+// OGN address type (2 bit) from the ID in the position comment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressType {
+    Random,
+    Icao,
+    Flarm,
+    Ogn,
+}
+
+impl AddressType {
+    pub fn new(value: u16) -> Option<Self> {
+        match value {
+            0 => Some(AddressType::Random),
+            1 => Some(AddressType::Icao),
+            2 => Some(AddressType::Flarm),
+            3 => Some(AddressType::Ogn),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AddressType::Random => "random",
+            AddressType::Icao => "icao",
+            AddressType::Flarm => "flarm",
+            AddressType::Ogn => "ogn",
+        }
+    }
+}
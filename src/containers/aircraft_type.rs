@@ -0,0 +1,66 @@
+use serde::Serialize;
+
+// OGN aircraft type (4 bit) from the ID in the position comment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AircraftType {
+    Unknown,
+    Glider,
+    TowPlane,
+    Helicopter,
+    Skydiver,
+    DropPlane,
+    HangGlider,
+    Paraglider,
+    PoweredAircraft,
+    JetAircraft,
+    Balloon,
+    Airship,
+    Uav,
+    Reserved,
+    StaticObject,
+}
+
+impl From<u8> for AircraftType {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => AircraftType::Glider,
+            2 => AircraftType::TowPlane,
+            3 => AircraftType::Helicopter,
+            4 => AircraftType::Skydiver,
+            5 => AircraftType::DropPlane,
+            6 => AircraftType::HangGlider,
+            7 => AircraftType::Paraglider,
+            8 => AircraftType::PoweredAircraft,
+            9 => AircraftType::JetAircraft,
+            11 => AircraftType::Balloon,
+            12 => AircraftType::Airship,
+            13 => AircraftType::Uav,
+            14 => AircraftType::Reserved,
+            15 => AircraftType::StaticObject,
+            _ => AircraftType::Unknown,
+        }
+    }
+}
+
+impl AircraftType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AircraftType::Unknown => "unknown",
+            AircraftType::Glider => "glider",
+            AircraftType::TowPlane => "tow_plane",
+            AircraftType::Helicopter => "helicopter",
+            AircraftType::Skydiver => "skydiver",
+            AircraftType::DropPlane => "drop_plane",
+            AircraftType::HangGlider => "hang_glider",
+            AircraftType::Paraglider => "paraglider",
+            AircraftType::PoweredAircraft => "powered_aircraft",
+            AircraftType::JetAircraft => "jet_aircraft",
+            AircraftType::Balloon => "balloon",
+            AircraftType::Airship => "airship",
+            AircraftType::Uav => "uav",
+            AircraftType::Reserved => "reserved",
+            AircraftType::StaticObject => "static_object",
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use chrono::prelude::*;
+use serde::Serialize;
+
+// a receiver alert is raised when a rule is violated and cleared when the receiver is fine again
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertContainer {
+    pub ts: DateTime<Utc>,
+    pub receiver: String,
+
+    // violated rule (e.g. "cpu_temperature>75") or "beacon_gap"
+    pub rule: String,
+    pub value: Option<f64>,
+    pub active: bool,
+    pub message: String,
+}
@@ -1,7 +1,9 @@
 use chrono::prelude::*;
 
+#[derive(Debug, Clone)]
 pub struct CommentContainer {
     // Fields from ServerResponseContainer
+    #[allow(dead_code)]
     pub ts: DateTime<Utc>,
     pub raw_message: String,
 }
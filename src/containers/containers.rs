@@ -1,20 +1,89 @@
+use actix::prelude::*;
 use ogn_parser::{AprsData, ServerResponse};
 
 use crate::{
     containers::{
-        comment_container::CommentContainer, parser_error_container::ParserErrorContainer,
-        position_container::PositionContainer, server_comment_container::ServerCommentContainer,
+        address_type::AddressType,
+        aircraft_type::AircraftType,
+        alert_container::AlertContainer,
+        comment_container::CommentContainer,
+        coverage_container::CoverageContainer,
+        device_container::DeviceInventoryContainer,
+        flight_container::FlightContainer,
+        fused_position_container::FusedPositionContainer,
+        parser_error_container::ParserErrorContainer,
+        polar_container::PolarContainer,
+        position_container::PositionContainer,
+        protocol::Protocol,
+        qnh_container::QnhContainer,
+        receiver_inventory_container::{ReceiverInventoryContainer, ReceiverVersionContainer},
+        sensitivity_container::SensitivityContainer,
+        server_comment_container::ServerCommentContainer,
         status_container::StatusContainer,
+        thermal_container::{ThermalContainer, ThermalMapContainer},
+        timing_container::TimingContainer,
+        units::Units,
+        weather_container::{WeatherContainer, is_weather},
+        wind_container::WindContainer,
     },
     messages::server_response_container::ServerResponseContainer,
+    utils::icao,
 };
 
+// Containers are either converted from a ServerResponseContainer (in the output actors)
+// or derived from several messages (in the processing actors, sent directly as message)
+#[derive(Message, Debug, Clone)]
+#[rtype(result = "()")]
 pub enum Container {
     Position(PositionContainer),
     Status(StatusContainer),
     ServerComment(ServerCommentContainer),
     ParserError(ParserErrorContainer),
     Comment(CommentContainer),
+    Weather(WeatherContainer),
+
+    // Derived containers
+    FusedPosition(FusedPositionContainer),
+    Flight(FlightContainer),
+    Thermal(ThermalContainer),
+    ThermalMap(ThermalMapContainer),
+    Wind(WindContainer),
+    Qnh(QnhContainer),
+    Coverage(CoverageContainer),
+    Polar(PolarContainer),
+    Alert(AlertContainer),
+    Timing(TimingContainer),
+    Sensitivity(SensitivityContainer),
+    ReceiverInventory(ReceiverInventoryContainer),
+    ReceiverVersion(ReceiverVersionContainer),
+    DeviceInventory(DeviceInventoryContainer),
+}
+
+impl Container {
+    // convert the container to the unit system of the output, all containers are created with aviation units
+    pub fn with_units(self, units: Units) -> Self {
+        if units == Units::Aviation {
+            return self;
+        }
+
+        match self {
+            Container::Position(position) => Container::Position(position.into_si()),
+            Container::Weather(weather) => Container::Weather(weather.into_si()),
+            Container::FusedPosition(fused_position) => {
+                Container::FusedPosition(fused_position.into_si())
+            }
+            Container::Flight(flight) => Container::Flight(flight.into_si()),
+            Container::Thermal(thermal) => Container::Thermal(thermal.into_si()),
+            Container::ThermalMap(thermal_map) => Container::ThermalMap(thermal_map.into_si()),
+            Container::Wind(wind) => Container::Wind(wind.into_si()),
+            Container::Coverage(coverage) => Container::Coverage(coverage.into_si()),
+            Container::Polar(polar) => Container::Polar(polar.into_si()),
+            Container::ReceiverInventory(inventory) => {
+                Container::ReceiverInventory(inventory.into_si())
+            }
+            _ => self,
+        }
+    }
 }
 
 // from trait implementation for server response container to Container
@@ -22,19 +91,41 @@ impl From<ServerResponseContainer> for Container {
     fn from(server_response_container: ServerResponseContainer) -> Self {
         match server_response_container.server_response {
             ServerResponse::AprsPacket(packet) => match packet.data {
+                AprsData::Position(position) if is_weather(&position) => {
+                    Container::Weather(WeatherContainer::new(
+                        server_response_container.ts,
+                        server_response_container.raw_message,
+                        server_response_container.receiver_ts,
+                        packet.from,
+                        packet.to,
+                        packet.via.last().cloned(),
+                        position,
+                    ))
+                }
                 AprsData::Position(position) => {
+                    let protocol = Protocol::from_dst_call(&packet.to.to_string());
                     let mut container = PositionContainer {
                         ts: server_response_container.ts,
+                        units: Units::Aviation,
                         raw_message: server_response_container.raw_message,
                         receiver_ts: server_response_container.receiver_ts,
                         bearing: server_response_container.bearing,
                         distance: server_response_container.distance,
+                        slant_range: server_response_container.slant_range,
+                        elevation_angle: server_response_container.elevation_angle,
+                        altitude_difference: server_response_container.altitude_difference,
                         normalized_quality: server_response_container.normalized_signal_quality,
                         plausibility: server_response_container.plausibility,
+                        airfield: server_response_container.airfield,
+                        airfield_distance: server_response_container.airfield_distance,
+                        pressure_altitude: server_response_container.pressure_altitude,
+                        qnh: server_response_container.qnh,
+                        altitude_estimated: server_response_container.altitude_estimated,
 
                         src_call: packet.from,
                         dst_call: packet.to,
                         receiver: packet.via.last().cloned(),
+                        protocol,
 
                         receiver_time: position.timestamp,
                         messaging_supported: position.messaging_supported,
@@ -48,17 +139,8 @@ impl From<ServerResponseContainer> for Container {
                         course: position.comment.course,
                         speed: position.comment.speed,
                         altitude: position.comment.altitude,
-                        wind_direction: position.comment.wind_direction,
-                        wind_speed: position.comment.wind_speed,
-                        gust: position.comment.gust,
-                        temperature: position.comment.temperature,
-                        rainfall_1h: position.comment.rainfall_1h,
-                        rainfall_24h: position.comment.rainfall_24h,
-                        rainfall_midnight: position.comment.rainfall_midnight,
-                        humidity: position.comment.humidity,
-                        barometric_pressure: position.comment.barometric_pressure,
                         additional_precision: position.comment.additional_precision,
-                        climb_rate: position.comment.climb_rate,
+                        climb_rate: position.comment.climb_rate.map(f64::from),
                         turn_rate: position.comment.turn_rate,
                         signal_quality: position.comment.signal_quality,
                         error: position.comment.error,
@@ -74,6 +156,10 @@ impl From<ServerResponseContainer> for Container {
                         reserved: None,
                         address_type: None,
                         aircraft_type: None,
+                        address_type_name: None,
+                        aircraft_type_name: None,
+                        country: None,
+                        registration: None,
                         is_stealth: None,
                         is_notrack: None,
                         address: None,
@@ -86,6 +172,21 @@ impl From<ServerResponseContainer> for Container {
                         container.is_stealth = Some(id.is_stealth);
                         container.is_notrack = Some(id.is_notrack);
                         container.address = Some(id.address);
+                    } else if let Some((address_type, address)) =
+                        protocol.address_from_callsign(&container.src_call.to_string())
+                    {
+                        // e.g. ADS-B messages have the ICAO address only in the callsign
+                        container.address_type = address_type;
+                        container.address = Some(address);
+                    }
+                    container.address_type_name = container.address_type.and_then(AddressType::new);
+                    container.aircraft_type_name = container.aircraft_type.map(AircraftType::from);
+
+                    if container.address_type_name == Some(AddressType::Icao)
+                        && let Some(address) = container.address
+                    {
+                        container.country = icao::country(address);
+                        container.registration = icao::n_number(address);
                     }
 
                     Container::Position(container)
@@ -96,6 +197,7 @@ impl From<ServerResponseContainer> for Container {
                     receiver_ts: server_response_container.receiver_ts,
 
                     src_call: packet.from,
+                    protocol: Protocol::from_dst_call(&packet.to.to_string()),
                     dst_call: packet.to,
                     receiver: packet.via.last().cloned(),
 
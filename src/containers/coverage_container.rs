@@ -0,0 +1,133 @@
+use chrono::prelude::*;
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::containers::{
+    position_container::serialize_location,
+    units::{self, Units},
+};
+
+// statistics of the positions a receiver got from one grid cell
+#[derive(Debug, Clone, Serialize)]
+pub struct CoverageCell {
+    pub ts: DateTime<Utc>,
+    pub units: Units,
+    pub receiver: String,
+
+    // centre and size [°] of the cell
+    #[serde(serialize_with = "serialize_location")]
+    pub location: (f64, f64),
+    pub resolution: f64,
+
+    pub message_count: u32,
+    pub max_normalized_quality: Option<f64>,
+    pub mean_normalized_quality: Option<f64>,
+    pub min_altitude: Option<u32>,
+    pub max_distance: Option<f64>,
+
+    // positions since the last snapshot, the database adds them to the stored statistics
+    #[serde(skip)]
+    pub new_message_count: u32,
+    #[serde(skip)]
+    pub new_quality_count: u32,
+    #[serde(skip)]
+    pub new_quality_sum: f64,
+}
+
+// PostgreSQL row with the statistics since the last snapshot, merged with the stored statistics by the upsert
+#[derive(Debug, Serialize)]
+pub struct CoverageRow<'a> {
+    pub ts: DateTime<Utc>,
+    pub units: Units,
+    pub receiver: &'a str,
+    #[serde(serialize_with = "serialize_location")]
+    pub location: (f64, f64),
+    pub resolution: f64,
+    pub message_count: u32,
+    pub quality_count: u32,
+    pub max_normalized_quality: Option<f64>,
+    pub mean_normalized_quality: Option<f64>,
+    pub min_altitude: Option<u32>,
+    pub max_distance: Option<f64>,
+}
+
+impl CoverageCell {
+    pub fn to_row(&self) -> CoverageRow<'_> {
+        CoverageRow {
+            ts: self.ts,
+            units: self.units,
+            receiver: &self.receiver,
+            location: self.location,
+            resolution: self.resolution,
+            message_count: self.new_message_count,
+            quality_count: self.new_quality_count,
+            max_normalized_quality: self.max_normalized_quality,
+            mean_normalized_quality: (self.new_quality_count > 0)
+                .then(|| self.new_quality_sum / self.new_quality_count as f64),
+            min_altitude: self.min_altitude,
+            max_distance: self.max_distance,
+        }
+    }
+}
+
+// snapshot of the coverage of all receivers
+#[derive(Debug, Clone, Serialize)]
+pub struct CoverageContainer {
+    pub ts: DateTime<Utc>,
+    pub units: Units,
+    pub cells: Vec<CoverageCell>,
+}
+
+impl CoverageContainer {
+    // convert the min. altitude from [ft] to [m]
+    pub fn into_si(mut self) -> Self {
+        if self.units == Units::Si {
+            return self;
+        }
+
+        self.units = Units::Si;
+        for cell in &mut self.cells {
+            cell.units = Units::Si;
+            cell.min_altitude = cell.min_altitude.map(units::feet_to_metres);
+        }
+        self
+    }
+
+    // every cell as polygon feature of a GeoJSON feature collection
+    pub fn to_geojson(&self) -> Value {
+        let features = self
+            .cells
+            .iter()
+            .map(|cell| {
+                let (longitude, latitude) = cell.location;
+                let half = cell.resolution / 2.0;
+                let (west, east) = (longitude - half, longitude + half);
+                let (south, north) = (latitude - half, latitude + half);
+                json!({
+                    "type": "Feature",
+                    "geometry": {
+                        "type": "Polygon",
+                        "coordinates": [[
+                            [west, south], [east, south], [east, north], [west, north], [west, south]
+                        ]]
+                    },
+                    "properties": {
+                        "receiver": cell.receiver,
+                        "message_count": cell.message_count,
+                        "max_normalized_quality": cell.max_normalized_quality,
+                        "mean_normalized_quality": cell.mean_normalized_quality,
+                        "min_altitude": cell.min_altitude,
+                        "max_distance": cell.max_distance,
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "type": "FeatureCollection",
+            "ts": self.ts,
+            "units": self.units,
+            "features": features,
+        })
+    }
+}
@@ -0,0 +1,39 @@
+use chrono::prelude::*;
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::containers::{address_type::AddressType, aircraft_type::AircraftType};
+
+// aggregated state of a device (FLARM, OGN tracker, ...) from its positions
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceInventory {
+    pub address: u32,
+    pub address_type: u16,
+    pub address_type_name: Option<AddressType>,
+    pub aircraft_type: u8,
+    pub aircraft_type_name: AircraftType,
+    pub src_call: String,
+
+    // latest firmware and hardware
+    pub software_version: Option<Decimal>,
+    pub hardware_version: Option<u8>,
+    pub original_address: Option<u32>,
+
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+
+    // statistics since the last inventory (summed up by the database): positions, mean frequency offset [kHz] and
+    // all receivers the device was heard by (as JSON list)
+    pub message_count: u32,
+    pub frequency_offset_count: u32,
+    pub mean_frequency_offset: Option<f64>,
+    pub receiver_count: usize,
+    pub receivers: String,
+}
+
+// devices which were seen since the last inventory
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceInventoryContainer {
+    pub ts: DateTime<Utc>,
+    pub devices: Vec<DeviceInventory>,
+}
@@ -0,0 +1,174 @@
+use std::time::UNIX_EPOCH;
+
+use chrono::prelude::*;
+use influxlp_tools::LineProtocol;
+use ogn_parser::Callsign;
+use serde::Serialize;
+
+use crate::containers::{
+    address_type::AddressType,
+    aircraft_type::AircraftType,
+    position_container::serialize_optional_location,
+    protocol::Protocol,
+    units::{self, Units},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchMethod {
+    Aerotow,
+    Winch,
+    SelfLaunch,
+    // no tracked tow plane, no winch climb and no powered climb (e.g. launch ended by a rope break)
+    Unknown,
+}
+
+impl LaunchMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LaunchMethod::Aerotow => "aerotow",
+            LaunchMethod::Winch => "winch",
+            LaunchMethod::SelfLaunch => "self_launch",
+            LaunchMethod::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FlightContainer {
+    pub units: Units,
+
+    // Fields from AprsPacket
+    pub src_call: Callsign,
+    pub dst_call: Callsign,
+    pub protocol: Protocol,
+
+    // Fields from ID
+    pub address_type: Option<u16>,
+    pub aircraft_type: Option<u8>,
+    pub address_type_name: Option<AddressType>,
+    pub aircraft_type_name: Option<AircraftType>,
+    pub address: Option<u32>,
+
+    // Takeoff (not available if the aircraft was first seen in the air)
+    pub takeoff_ts: Option<DateTime<Utc>>,
+    #[serde(serialize_with = "serialize_optional_location")]
+    pub takeoff_location: Option<(f64, f64)>,
+    pub takeoff_airfield: Option<String>,
+    pub takeoff_runway: Option<String>,
+
+    // Landing (not available if the aircraft disappeared in the air)
+    pub landing_ts: Option<DateTime<Utc>>,
+    #[serde(serialize_with = "serialize_optional_location")]
+    pub landing_location: Option<(f64, f64)>,
+    pub landing_airfield: Option<String>,
+    pub landing_runway: Option<String>,
+
+    // Launch (gliders only), the release height is relative to the takeoff altitude
+    pub launch_method: Option<LaunchMethod>,
+    pub tow_plane: Option<String>,
+    pub release_height: Option<u32>,
+
+    // Flight summary
+    pub max_altitude: Option<u32>,
+    pub duration: Option<i64>,
+}
+
+impl FlightContainer {
+    // convert the heights from the OGN units to SI units
+    pub fn into_si(mut self) -> Self {
+        if self.units == Units::Si {
+            return self;
+        }
+
+        self.units = Units::Si;
+        self.release_height = self.release_height.map(units::feet_to_metres);
+        self.max_altitude = self.max_altitude.map(units::feet_to_metres);
+        self
+    }
+
+    pub fn to_ilp(&self) -> String {
+        let mut lp = LineProtocol::new("flights");
+
+        lp = lp.add_tag("src_call", self.src_call.to_string());
+        lp = lp.add_tag("dst_call", self.dst_call.to_string());
+        lp = lp.add_tag("protocol", self.protocol.as_str());
+        lp = lp.add_tag("units", self.units.as_str());
+
+        // Fields from ID
+        if let Some(address_type) = self.address_type {
+            lp = lp.add_field("address_type", address_type);
+        }
+        if let Some(aircraft_type) = self.aircraft_type {
+            lp = lp.add_field("aircraft_type", aircraft_type);
+        }
+        if let Some(address_type_name) = self.address_type_name {
+            lp = lp.add_field("address_type_name", address_type_name.as_str());
+        }
+        if let Some(aircraft_type_name) = self.aircraft_type_name {
+            lp = lp.add_field("aircraft_type_name", aircraft_type_name.as_str());
+        }
+        if let Some(address) = self.address {
+            lp = lp.add_field("address", address);
+        }
+
+        // Takeoff
+        if let Some(takeoff_ts) = self.takeoff_ts {
+            lp = lp.add_field("takeoff_ts", takeoff_ts.to_rfc3339());
+        }
+        if let Some((longitude, latitude)) = self.takeoff_location {
+            lp = lp.add_field("takeoff_latitude", latitude);
+            lp = lp.add_field("takeoff_longitude", longitude);
+        }
+        if let Some(takeoff_airfield) = &self.takeoff_airfield {
+            lp = lp.add_field("takeoff_airfield", takeoff_airfield);
+        }
+        if let Some(takeoff_runway) = &self.takeoff_runway {
+            lp = lp.add_field("takeoff_runway", takeoff_runway);
+        }
+
+        // Landing
+        if let Some(landing_ts) = self.landing_ts {
+            lp = lp.add_field("landing_ts", landing_ts.to_rfc3339());
+        }
+        if let Some((longitude, latitude)) = self.landing_location {
+            lp = lp.add_field("landing_latitude", latitude);
+            lp = lp.add_field("landing_longitude", longitude);
+        }
+        if let Some(landing_airfield) = &self.landing_airfield {
+            lp = lp.add_field("landing_airfield", landing_airfield);
+        }
+        if let Some(landing_runway) = &self.landing_runway {
+            lp = lp.add_field("landing_runway", landing_runway);
+        }
+
+        // Launch
+        if let Some(launch_method) = self.launch_method {
+            lp = lp.add_field("launch_method", launch_method.as_str());
+        }
+        if let Some(tow_plane) = &self.tow_plane {
+            lp = lp.add_field("tow_plane", tow_plane);
+        }
+        if let Some(release_height) = self.release_height {
+            lp = lp.add_field("release_height", release_height);
+        }
+
+        // Flight summary
+        if let Some(max_altitude) = self.max_altitude {
+            lp = lp.add_field("max_altitude", max_altitude);
+        }
+        if let Some(duration) = self.duration {
+            lp = lp.add_field("duration", duration);
+        }
+
+        // the flight is stored at takeoff time (or at landing time if takeoff is unknown)
+        let ts = self.takeoff_ts.or(self.landing_ts).unwrap_or_default();
+        let lp = lp.with_timestamp(
+            ts.signed_duration_since(DateTime::<Utc>::from(UNIX_EPOCH))
+                .num_nanoseconds()
+                .unwrap(),
+        );
+
+        lp.build().unwrap()
+    }
+}
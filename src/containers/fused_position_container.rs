@@ -0,0 +1,263 @@
+use std::time::UNIX_EPOCH;
+
+use chrono::prelude::*;
+use influxlp_tools::LineProtocol;
+use ogn_parser::{Callsign, Timestamp};
+use rust_decimal::prelude::*;
+use serde::Serialize;
+
+use crate::containers::{
+    address_type::AddressType,
+    aircraft_type::AircraftType,
+    position_container::{PositionContainer, serialize_location},
+    protocol::Protocol,
+    units::{self, Units},
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FusedReceiver {
+    pub receiver: Option<Callsign>,
+    pub ts: DateTime<Utc>,
+    pub bearing: Option<f64>,
+    pub distance: Option<f64>,
+    pub slant_range: Option<f64>,
+    pub elevation_angle: Option<f64>,
+    pub signal_quality: Option<Decimal>,
+    pub normalized_quality: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FusedPositionContainer {
+    // Fields from the first received copy
+    pub ts: DateTime<Utc>,
+    pub units: Units,
+    pub receiver_ts: Option<DateTime<Utc>>,
+
+    // Fields from AprsPacket
+    pub src_call: Callsign,
+    pub dst_call: Callsign,
+    pub protocol: Protocol,
+
+    // Fields from AprsPosition
+    pub receiver_time: Option<Timestamp>,
+    #[serde(serialize_with = "serialize_location")]
+    pub location: (f64, f64),
+
+    // Fields from PositionComment
+    pub course: Option<u16>,
+    pub speed: Option<u16>,
+    pub altitude: Option<u32>,
+    pub climb_rate: Option<f64>,
+    pub turn_rate: Option<Decimal>,
+
+    // Fields from ID
+    pub address_type: Option<u16>,
+    pub aircraft_type: Option<u8>,
+    pub address_type_name: Option<AddressType>,
+    pub aircraft_type_name: Option<AircraftType>,
+    pub address: Option<u32>,
+
+    // Fields from all received copies
+    pub receiver_count: usize,
+    pub receivers: Vec<FusedReceiver>,
+}
+
+// PostgreSQL (CSV) can't handle nested lists, so the receivers are stored as JSON
+#[derive(Debug, Serialize)]
+pub struct FusedPositionRow<'a> {
+    pub ts: DateTime<Utc>,
+    pub units: Units,
+    pub receiver_ts: Option<DateTime<Utc>>,
+    pub src_call: &'a Callsign,
+    pub dst_call: &'a Callsign,
+    pub protocol: Protocol,
+    pub receiver_time: &'a Option<Timestamp>,
+    #[serde(serialize_with = "serialize_location")]
+    pub location: (f64, f64),
+    pub course: Option<u16>,
+    pub speed: Option<u16>,
+    pub altitude: Option<u32>,
+    pub climb_rate: Option<f64>,
+    pub turn_rate: Option<Decimal>,
+    pub address_type: Option<u16>,
+    pub aircraft_type: Option<u8>,
+    pub address_type_name: Option<AddressType>,
+    pub aircraft_type_name: Option<AircraftType>,
+    pub address: Option<u32>,
+    pub receiver_count: usize,
+    pub receivers: String,
+}
+
+impl FusedPositionContainer {
+    // all copies must belong to the same transmission, the first copy is the reference
+    pub fn new(copies: &[PositionContainer]) -> Self {
+        let first = &copies[0];
+
+        FusedPositionContainer {
+            ts: first.ts,
+            units: first.units,
+            receiver_ts: first.receiver_ts,
+
+            src_call: first.src_call.clone(),
+            dst_call: first.dst_call.clone(),
+            protocol: first.protocol,
+
+            receiver_time: first.receiver_time.clone(),
+            location: first.location,
+
+            course: first.course,
+            speed: first.speed,
+            altitude: first.altitude,
+            climb_rate: first.climb_rate,
+            turn_rate: first.turn_rate,
+
+            address_type: first.address_type,
+            aircraft_type: first.aircraft_type,
+            address_type_name: first.address_type_name,
+            aircraft_type_name: first.aircraft_type_name,
+            address: first.address,
+
+            receiver_count: copies.len(),
+            receivers: copies
+                .iter()
+                .map(|copy| FusedReceiver {
+                    receiver: copy.receiver.clone(),
+                    ts: copy.ts,
+                    bearing: copy.bearing,
+                    distance: copy.distance,
+                    slant_range: copy.slant_range,
+                    elevation_angle: copy.elevation_angle,
+                    signal_quality: copy.signal_quality,
+                    normalized_quality: copy.normalized_quality,
+                })
+                .collect(),
+        }
+    }
+
+    // convert speed, altitude, climb and turn rate from the OGN units to SI units
+    pub fn into_si(mut self) -> Self {
+        if self.units == Units::Si {
+            return self;
+        }
+
+        self.units = Units::Si;
+        self.speed = self.speed.map(units::knots_to_kmh);
+        self.altitude = self.altitude.map(units::feet_to_metres);
+        self.climb_rate = self.climb_rate.map(units::fpm_to_mps);
+        self.turn_rate = self.turn_rate.map(units::rot_to_dps);
+        self
+    }
+
+    pub fn to_row(&self) -> FusedPositionRow<'_> {
+        FusedPositionRow {
+            ts: self.ts,
+            units: self.units,
+            receiver_ts: self.receiver_ts,
+            src_call: &self.src_call,
+            dst_call: &self.dst_call,
+            protocol: self.protocol,
+            receiver_time: &self.receiver_time,
+            location: self.location,
+            course: self.course,
+            speed: self.speed,
+            altitude: self.altitude,
+            climb_rate: self.climb_rate,
+            turn_rate: self.turn_rate,
+            address_type: self.address_type,
+            aircraft_type: self.aircraft_type,
+            address_type_name: self.address_type_name,
+            aircraft_type_name: self.aircraft_type_name,
+            address: self.address,
+            receiver_count: self.receiver_count,
+            receivers: serde_json::to_string(&self.receivers).unwrap(),
+        }
+    }
+
+    pub fn to_ilp(&self) -> String {
+        let mut lp = LineProtocol::new("fused_positions");
+
+        lp = lp.add_tag("src_call", self.src_call.to_string());
+        lp = lp.add_tag("dst_call", self.dst_call.to_string());
+        lp = lp.add_tag("protocol", self.protocol.as_str());
+        lp = lp.add_tag("units", self.units.as_str());
+
+        if let Some(ts) = self.receiver_ts {
+            lp = lp.add_field("receiver_ts", ts.to_rfc3339());
+        }
+        if let Some(receiver_time) = &self.receiver_time {
+            lp = lp.add_field("receiver_time", receiver_time.to_string());
+        }
+        lp = lp.add_field("latitude", self.location.1);
+        lp = lp.add_field("longitude", self.location.0);
+
+        if let Some(course) = self.course {
+            lp = lp.add_field("course", course);
+        }
+        if let Some(speed) = self.speed {
+            lp = lp.add_field("speed", speed);
+        }
+        if let Some(altitude) = self.altitude {
+            lp = lp.add_field("altitude", altitude);
+        }
+        if let Some(climb_rate) = self.climb_rate {
+            // in aviation units the climb rate [fpm] stays an integer field
+            lp = match self.units {
+                Units::Aviation => lp.add_field("climb_rate", climb_rate.round() as i64),
+                Units::Si => lp.add_field("climb_rate", climb_rate),
+            };
+        }
+        if let Some(turn_rate) = &self.turn_rate {
+            lp = lp.add_field("turn_rate", turn_rate.to_f64().unwrap());
+        }
+        if let Some(address_type) = self.address_type {
+            lp = lp.add_field("address_type", address_type);
+        }
+        if let Some(aircraft_type) = self.aircraft_type {
+            lp = lp.add_field("aircraft_type", aircraft_type);
+        }
+        if let Some(address_type_name) = self.address_type_name {
+            lp = lp.add_field("address_type_name", address_type_name.as_str());
+        }
+        if let Some(aircraft_type_name) = self.aircraft_type_name {
+            lp = lp.add_field("aircraft_type_name", aircraft_type_name.as_str());
+        }
+        if let Some(address) = self.address {
+            lp = lp.add_field("address", address);
+        }
+
+        lp = lp.add_field("receiver_count", self.receiver_count as u64);
+        lp = lp.add_field(
+            "receivers",
+            self.receivers
+                .iter()
+                .filter_map(|r| r.receiver.as_ref().map(|c| c.to_string()))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        if let Some(max_distance) = self
+            .receivers
+            .iter()
+            .filter_map(|r| r.distance)
+            .reduce(f64::max)
+        {
+            lp = lp.add_field("max_distance", max_distance);
+        }
+        if let Some(max_signal_quality) = self
+            .receivers
+            .iter()
+            .filter_map(|r| r.signal_quality.and_then(|sq| sq.to_f64()))
+            .reduce(f64::max)
+        {
+            lp = lp.add_field("max_signal_quality", max_signal_quality);
+        }
+
+        let lp = lp.with_timestamp(
+            self.ts
+                .signed_duration_since(DateTime::<Utc>::from(UNIX_EPOCH))
+                .num_nanoseconds()
+                .unwrap(),
+        );
+
+        lp.build().unwrap()
+    }
+}
@@ -1,6 +1,24 @@
+pub mod address_type;
+pub mod aircraft_type;
+pub mod alert_container;
 pub mod comment_container;
+#[allow(clippy::module_inception)]
 pub mod containers;
+pub mod coverage_container;
+pub mod device_container;
+pub mod flight_container;
+pub mod fused_position_container;
 pub mod parser_error_container;
+pub mod polar_container;
 pub mod position_container;
+pub mod protocol;
+pub mod qnh_container;
+pub mod receiver_inventory_container;
+pub mod sensitivity_container;
 pub mod server_comment_container;
 pub mod status_container;
+pub mod thermal_container;
+pub mod timing_container;
+pub mod units;
+pub mod weather_container;
+pub mod wind_container;
@@ -6,6 +6,7 @@ pub struct ParserErrorContainer {
     // Fields from ServerResponseContainer
     pub ts: DateTime<Utc>,
     #[serde(skip_serializing)]
+    #[allow(dead_code)]
     pub raw_message: String,
 
     pub error_message: String,
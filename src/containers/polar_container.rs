@@ -0,0 +1,128 @@
+use std::fmt::Write;
+
+use chrono::prelude::*;
+use serde::Serialize;
+
+use crate::containers::units::{self, Units};
+
+// colours of the altitude bands in the SVG diagram (lowest band first)
+const COLOURS: [&str; 6] = [
+    "#d62728", "#ff7f0e", "#bcbd22", "#2ca02c", "#1f77b4", "#9467bd",
+];
+
+// range statistics of a receiver in one bearing sector and altitude band
+#[derive(Debug, Clone, Serialize)]
+pub struct PolarSector {
+    pub ts: DateTime<Utc>,
+    pub units: Units,
+    pub receiver: String,
+
+    // centre of the bearing sector [°] and lower bound of the altitude band (relative to the receiver)
+    pub bearing: f64,
+    pub altitude_band: i32,
+
+    // ranges [m]
+    pub message_count: u32,
+    pub max_range: f64,
+    pub percentile_range: f64,
+}
+
+// polar diagram of a receiver
+#[derive(Debug, Clone, Serialize)]
+pub struct PolarContainer {
+    pub ts: DateTime<Utc>,
+    pub units: Units,
+    pub receiver: String,
+    pub sector_size: f64,
+    pub percentile: f64,
+    pub sectors: Vec<PolarSector>,
+}
+
+impl PolarContainer {
+    // convert the altitude bands from [ft] to [m]
+    pub fn into_si(mut self) -> Self {
+        if self.units == Units::Si {
+            return self;
+        }
+
+        self.units = Units::Si;
+        for sector in &mut self.sectors {
+            sector.units = Units::Si;
+            sector.altitude_band = units::feet_to_metres_signed(sector.altitude_band);
+        }
+        self
+    }
+
+    // percentile range per bearing sector as one polygon per altitude band, with range rings every 10km
+    pub fn to_svg(&self) -> String {
+        const SIZE: f64 = 600.0;
+        const RADIUS: f64 = 280.0;
+
+        let max_range = self
+            .sectors
+            .iter()
+            .map(|sector| sector.percentile_range)
+            .fold(10_000.0, f64::max);
+        let rings = (max_range / 10_000.0).ceil();
+        let scale = RADIUS / (rings * 10_000.0);
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{SIZE}" height="{SIZE}" viewBox="{} {} {SIZE} {SIZE}">"#,
+            -SIZE / 2.0,
+            -SIZE / 2.0
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-family="sans-serif" font-size="14">{} ({})</text>"#,
+            -SIZE / 2.0 + 5.0,
+            -SIZE / 2.0 + 15.0,
+            self.receiver,
+            self.ts.to_rfc3339()
+        );
+        for ring in 1..=rings as u32 {
+            let _ = writeln!(
+                svg,
+                r##"<circle r="{:.1}" fill="none" stroke="#ccc"/><text x="2" y="{:.1}" font-family="sans-serif" font-size="10" fill="#999">{}km</text>"##,
+                ring as f64 * 10_000.0 * scale,
+                -(ring as f64) * 10_000.0 * scale,
+                ring * 10
+            );
+        }
+
+        let mut bands = self
+            .sectors
+            .iter()
+            .map(|sector| sector.altitude_band)
+            .collect::<Vec<_>>();
+        bands.sort();
+        bands.dedup();
+        for (index, band) in bands.iter().enumerate() {
+            let mut sectors = self
+                .sectors
+                .iter()
+                .filter(|sector| sector.altitude_band == *band)
+                .collect::<Vec<_>>();
+            sectors.sort_by(|a, b| a.bearing.total_cmp(&b.bearing));
+
+            // north is up, the bearing is clockwise
+            let points = sectors
+                .iter()
+                .map(|sector| {
+                    let angle = sector.bearing.to_radians();
+                    let radius = sector.percentile_range * scale;
+                    format!("{:.1},{:.1}", radius * angle.sin(), -radius * angle.cos())
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            let colour = COLOURS[index % COLOURS.len()];
+            let _ = writeln!(
+                svg,
+                r#"<polygon points="{points}" fill="{colour}" fill-opacity="0.2" stroke="{colour}"><title>{band}</title></polygon>"#
+            );
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
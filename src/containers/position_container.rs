@@ -6,7 +6,14 @@ use ogn_parser::{AdditionalPrecision, Callsign, Latitude, Longitude, Timestamp};
 use rust_decimal::prelude::*;
 use serde::Serialize;
 
-fn serialize_location<S>(pos: &(f64, f64), serializer: S) -> Result<S::Ok, S::Error>
+use crate::containers::{
+    address_type::AddressType,
+    aircraft_type::AircraftType,
+    protocol::Protocol,
+    units::{self, Units},
+};
+
+pub fn serialize_location<S>(pos: &(f64, f64), serializer: S) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
 {
@@ -17,22 +24,45 @@ where
     serializer.serialize_str(&s)
 }
 
+pub fn serialize_optional_location<S>(
+    pos: &Option<(f64, f64)>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match pos {
+        Some(pos) => serialize_location(pos, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct PositionContainer {
     // Fields from ServerResponseContainer
     pub ts: DateTime<Utc>,
+    pub units: Units,
     #[serde(skip_serializing)]
     pub raw_message: String,
     pub receiver_ts: Option<DateTime<Utc>>,
     pub bearing: Option<f64>,
     pub distance: Option<f64>,
+    pub slant_range: Option<f64>,
+    pub elevation_angle: Option<f64>,
+    pub altitude_difference: Option<i32>,
     pub normalized_quality: Option<f64>,
     pub plausibility: Option<u16>,
+    pub airfield: Option<String>,
+    pub airfield_distance: Option<f64>,
+    pub pressure_altitude: Option<u32>,
+    pub qnh: Option<f64>,
+    pub altitude_estimated: Option<bool>,
 
     // Fields from AprsPacket
     pub src_call: Callsign,
     pub dst_call: Callsign,
     pub receiver: Option<Callsign>,
+    pub protocol: Protocol,
 
     // Fields from AprsPosition
     pub receiver_time: Option<Timestamp>,
@@ -53,26 +83,9 @@ pub struct PositionContainer {
     pub speed: Option<u16>,
     pub altitude: Option<u32>,
     #[serde(skip_serializing)]
-    pub wind_direction: Option<u16>,
-    #[serde(skip_serializing)]
-    pub wind_speed: Option<u16>,
-    #[serde(skip_serializing)]
-    pub gust: Option<u16>,
-    #[serde(skip_serializing)]
-    pub temperature: Option<i16>,
-    #[serde(skip_serializing)]
-    pub rainfall_1h: Option<u16>,
-    #[serde(skip_serializing)]
-    pub rainfall_24h: Option<u16>,
-    #[serde(skip_serializing)]
-    pub rainfall_midnight: Option<u16>,
-    #[serde(skip_serializing)]
-    pub humidity: Option<u8>,
-    #[serde(skip_serializing)]
-    pub barometric_pressure: Option<u32>,
-    #[serde(skip_serializing)]
+    #[allow(dead_code)]
     pub additional_precision: Option<AdditionalPrecision>,
-    pub climb_rate: Option<i16>,
+    pub climb_rate: Option<f64>,
     pub turn_rate: Option<Decimal>,
     pub signal_quality: Option<Decimal>,
     pub error: Option<u8>,
@@ -90,17 +103,41 @@ pub struct PositionContainer {
     pub reserved: Option<u16>,
     pub address_type: Option<u16>,
     pub aircraft_type: Option<u8>,
+    pub address_type_name: Option<AddressType>,
+    pub aircraft_type_name: Option<AircraftType>,
     pub is_stealth: Option<bool>,
     pub is_notrack: Option<bool>,
     pub address: Option<u32>,
+
+    // Fields derived from the ICAO address
+    pub country: Option<&'static str>,
+    pub registration: Option<String>,
 }
 
 impl PositionContainer {
+    // convert speed, altitude, climb and turn rate from the OGN units to SI units
+    pub fn into_si(mut self) -> Self {
+        if self.units == Units::Si {
+            return self;
+        }
+
+        self.units = Units::Si;
+        self.speed = self.speed.map(units::knots_to_kmh);
+        self.altitude = self.altitude.map(units::feet_to_metres);
+        self.pressure_altitude = self.pressure_altitude.map(units::feet_to_metres);
+        self.altitude_difference = self.altitude_difference.map(units::feet_to_metres_signed);
+        self.climb_rate = self.climb_rate.map(units::fpm_to_mps);
+        self.turn_rate = self.turn_rate.map(units::rot_to_dps);
+        self
+    }
+
     pub fn to_ilp(&self) -> String {
         let mut lp = LineProtocol::new("positions");
 
         lp = lp.add_tag("src_call", self.src_call.to_string());
         lp = lp.add_tag("dst_call", self.dst_call.to_string());
+        lp = lp.add_tag("protocol", self.protocol.as_str());
+        lp = lp.add_tag("units", self.units.as_str());
         if let Some(receiver) = &self.receiver {
             lp = lp.add_tag("receiver", receiver.to_string());
         }
@@ -116,12 +153,36 @@ impl PositionContainer {
         if let Some(distance) = self.distance {
             lp = lp.add_field("distance", distance);
         }
+        if let Some(slant_range) = self.slant_range {
+            lp = lp.add_field("slant_range", slant_range);
+        }
+        if let Some(elevation_angle) = self.elevation_angle {
+            lp = lp.add_field("elevation_angle", elevation_angle);
+        }
+        if let Some(altitude_difference) = self.altitude_difference {
+            lp = lp.add_field("altitude_difference", altitude_difference);
+        }
         if let Some(nq) = self.normalized_quality {
             lp = lp.add_field("normalized_quality", nq);
         }
         if let Some(plausibility) = self.plausibility {
             lp = lp.add_field("plausibility", plausibility);
         }
+        if let Some(airfield) = &self.airfield {
+            lp = lp.add_field("airfield", airfield);
+        }
+        if let Some(airfield_distance) = self.airfield_distance {
+            lp = lp.add_field("airfield_distance", airfield_distance);
+        }
+        if let Some(pressure_altitude) = self.pressure_altitude {
+            lp = lp.add_field("pressure_altitude", pressure_altitude);
+        }
+        if let Some(qnh) = self.qnh {
+            lp = lp.add_field("qnh", qnh);
+        }
+        if let Some(altitude_estimated) = self.altitude_estimated {
+            lp = lp.add_field("altitude_estimated", altitude_estimated);
+        }
 
         // Fields from AprsPosition
         if let Some(receiver_time) = &self.receiver_time {
@@ -143,38 +204,15 @@ impl PositionContainer {
         if let Some(altitude) = self.altitude {
             lp = lp.add_field("altitude", altitude);
         }
-        if let Some(wind_direction) = self.wind_direction {
-            lp = lp.add_field("wind_direction", wind_direction);
-        }
-        if let Some(wind_speed) = self.wind_speed {
-            lp = lp.add_field("wind_speed", wind_speed);
-        }
-        if let Some(gust) = self.gust {
-            lp = lp.add_field("gust", gust);
-        }
-        if let Some(temperature) = self.temperature {
-            lp = lp.add_field("temperature", temperature);
-        }
-        if let Some(rainfall_1h) = self.rainfall_1h {
-            lp = lp.add_field("rainfall_1h", rainfall_1h);
-        }
-        if let Some(rainfall_24h) = self.rainfall_24h {
-            lp = lp.add_field("rainfall_24h", rainfall_24h);
-        }
-        if let Some(rainfall_midnight) = self.rainfall_midnight {
-            lp = lp.add_field("rainfall_midnight", rainfall_midnight);
-        }
-        if let Some(humidity) = self.humidity {
-            lp = lp.add_field("humidity", humidity);
-        }
-        if let Some(barometric_pressure) = self.barometric_pressure {
-            lp = lp.add_field("barometric_pressure", barometric_pressure);
-        }
         /*if let Some(additional_precision) = &self.additional_precision {
             lp = lp.add_field("additional_precision", additional_precision.to_string());
         }*/
         if let Some(climb_rate) = self.climb_rate {
-            lp = lp.add_field("climb_rate", climb_rate);
+            // in aviation units the climb rate [fpm] stays an integer field
+            lp = match self.units {
+                Units::Aviation => lp.add_field("climb_rate", climb_rate.round() as i64),
+                Units::Si => lp.add_field("climb_rate", climb_rate),
+            };
         }
         if let Some(turn_rate) = &self.turn_rate {
             lp = lp.add_field("turn_rate", turn_rate.to_f64().unwrap());
@@ -220,6 +258,12 @@ impl PositionContainer {
         if let Some(aircraft_type) = self.aircraft_type {
             lp = lp.add_field("aircraft_type", aircraft_type);
         }
+        if let Some(address_type_name) = self.address_type_name {
+            lp = lp.add_field("address_type_name", address_type_name.as_str());
+        }
+        if let Some(aircraft_type_name) = self.aircraft_type_name {
+            lp = lp.add_field("aircraft_type_name", aircraft_type_name.as_str());
+        }
         if let Some(is_stealth) = self.is_stealth {
             lp = lp.add_field("is_stealth", is_stealth);
         }
@@ -230,6 +274,14 @@ impl PositionContainer {
             lp = lp.add_field("address", address);
         }
 
+        // Fields derived from the ICAO address
+        if let Some(country) = self.country {
+            lp = lp.add_field("country", country);
+        }
+        if let Some(registration) = &self.registration {
+            lp = lp.add_field("registration", registration);
+        }
+
         let lp = lp.with_timestamp(
             self.ts
                 .signed_duration_since(DateTime::<Utc>::from(UNIX_EPOCH))
@@ -0,0 +1,100 @@
+use serde::Serialize;
+
+// the destination callsign marks the protocol (or the device family) the message was received with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Protocol {
+    Flarm,
+    Fanet,
+    AdsB,
+    AdsL,
+    OgnTracker,
+    PilotAware,
+    SafeSky,
+    Spot,
+    Spider,
+    LiveTrack24,
+    Skylines,
+    Capturs,
+    Naviter,
+    Flymaster,
+    InReach,
+    Microtrak,
+    Dvs,
+    Receiver,
+    Unknown,
+}
+
+impl Protocol {
+    pub fn from_dst_call(dst_call: &str) -> Self {
+        match dst_call {
+            "OGFLR" | "OGFLR6" | "OGFLR7" => Protocol::Flarm,
+            "OGNFNT" => Protocol::Fanet,
+            "OGADSB" => Protocol::AdsB,
+            "OGADSL" => Protocol::AdsL,
+            "OGNTRK" => Protocol::OgnTracker,
+            "OGPAW" => Protocol::PilotAware,
+            "OGNSKY" => Protocol::SafeSky,
+            "OGSPOT" => Protocol::Spot,
+            "OGSPID" => Protocol::Spider,
+            "OGLT24" => Protocol::LiveTrack24,
+            "OGSKYL" => Protocol::Skylines,
+            "OGCAPT" => Protocol::Capturs,
+            "OGNAVI" => Protocol::Naviter,
+            "OGFLYM" => Protocol::Flymaster,
+            "OGINRE" => Protocol::InReach,
+            "OGNMTK" => Protocol::Microtrak,
+            "OGNDVS" => Protocol::Dvs,
+            "OGNSDR" | "APRS" => Protocol::Receiver,
+            _ => Protocol::Unknown,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Protocol::Flarm => "flarm",
+            Protocol::Fanet => "fanet",
+            Protocol::AdsB => "ads_b",
+            Protocol::AdsL => "ads_l",
+            Protocol::OgnTracker => "ogn_tracker",
+            Protocol::PilotAware => "pilot_aware",
+            Protocol::SafeSky => "safe_sky",
+            Protocol::Spot => "spot",
+            Protocol::Spider => "spider",
+            Protocol::LiveTrack24 => "live_track24",
+            Protocol::Skylines => "skylines",
+            Protocol::Capturs => "capturs",
+            Protocol::Naviter => "naviter",
+            Protocol::Flymaster => "flymaster",
+            Protocol::InReach => "in_reach",
+            Protocol::Microtrak => "microtrak",
+            Protocol::Dvs => "dvs",
+            Protocol::Receiver => "receiver",
+            Protocol::Unknown => "unknown",
+        }
+    }
+
+    // callsign prefix of senders without an ID in the comment (e.g. "ICA3D1C35") and the matching address type
+    fn callsign_prefix(&self) -> Option<(&'static str, Option<u16>)> {
+        match self {
+            Protocol::Flarm => Some(("FLR", Some(2))),
+            Protocol::AdsB => Some(("ICA", Some(1))),
+            Protocol::OgnTracker => Some(("OGN", Some(3))),
+            Protocol::Fanet => Some(("FNT", None)),
+            Protocol::PilotAware => Some(("PAW", None)),
+            Protocol::SafeSky => Some(("SKY", None)),
+            _ => None,
+        }
+    }
+
+    // derive address type and address from the callsign, if the comment has no ID
+    pub fn address_from_callsign(&self, src_call: &str) -> Option<(Option<u16>, u32)> {
+        let (prefix, address_type) = self.callsign_prefix()?;
+        let hex = src_call.strip_prefix(prefix)?;
+        if hex.len() != 6 {
+            return None;
+        }
+        let address = u32::from_str_radix(hex, 16).ok()?;
+        Some((address_type, address))
+    }
+}
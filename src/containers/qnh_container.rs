@@ -0,0 +1,41 @@
+use std::time::UNIX_EPOCH;
+
+use chrono::prelude::*;
+use influxlp_tools::LineProtocol;
+use serde::Serialize;
+
+use crate::containers::position_container::serialize_location;
+
+// regional QNH estimate from aircraft which send altitude and flight level
+#[derive(Debug, Clone, Serialize)]
+pub struct QnhContainer {
+    pub ts: DateTime<Utc>,
+
+    // centre of the region
+    #[serde(serialize_with = "serialize_location")]
+    pub location: (f64, f64),
+
+    pub qnh: f64, // [hPa] median of the samples
+    pub sample_count: u32,
+}
+
+impl QnhContainer {
+    pub fn to_ilp(&self) -> String {
+        let mut lp = LineProtocol::new("qnh");
+
+        lp = lp.add_tag("latitude", self.location.1.to_string());
+        lp = lp.add_tag("longitude", self.location.0.to_string());
+
+        lp = lp.add_field("qnh", self.qnh);
+        lp = lp.add_field("sample_count", self.sample_count);
+
+        let lp = lp.with_timestamp(
+            self.ts
+                .signed_duration_since(DateTime::<Utc>::from(UNIX_EPOCH))
+                .num_nanoseconds()
+                .unwrap(),
+        );
+
+        lp.build().unwrap()
+    }
+}
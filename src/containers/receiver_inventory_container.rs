@@ -0,0 +1,58 @@
+use chrono::prelude::*;
+use serde::Serialize;
+
+use crate::containers::{
+    position_container::serialize_optional_location,
+    units::{self, Units},
+};
+
+// current state of a receiver from its position and status beacons
+#[derive(Debug, Clone, Serialize)]
+pub struct ReceiverInventory {
+    pub receiver: String,
+    pub units: Units,
+
+    #[serde(serialize_with = "serialize_optional_location")]
+    pub location: Option<(f64, f64)>,
+    pub altitude: Option<u32>,
+
+    pub version: Option<String>,
+    pub platform: Option<String>,
+
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+// all known receivers
+#[derive(Debug, Clone, Serialize)]
+pub struct ReceiverInventoryContainer {
+    pub ts: DateTime<Utc>,
+    pub units: Units,
+    pub receivers: Vec<ReceiverInventory>,
+}
+
+impl ReceiverInventoryContainer {
+    // convert the altitudes from [ft] to [m]
+    pub fn into_si(mut self) -> Self {
+        if self.units == Units::Si {
+            return self;
+        }
+
+        self.units = Units::Si;
+        for receiver in &mut self.receivers {
+            receiver.units = Units::Si;
+            receiver.altitude = receiver.altitude.map(units::feet_to_metres);
+        }
+        self
+    }
+}
+
+// a receiver reported a new software version
+#[derive(Debug, Clone, Serialize)]
+pub struct ReceiverVersionContainer {
+    pub ts: DateTime<Utc>,
+    pub receiver: String,
+    pub previous_version: String,
+    pub version: String,
+    pub platform: Option<String>,
+}
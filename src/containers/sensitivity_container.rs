@@ -0,0 +1,40 @@
+use chrono::prelude::*;
+use serde::Serialize;
+
+use crate::containers::position_container::serialize_location;
+
+// relative sensitivity of a receiver within its region
+#[derive(Debug, Clone, Serialize)]
+pub struct SensitivityRank {
+    pub ts: DateTime<Utc>,
+
+    // centre of the region
+    #[serde(serialize_with = "serialize_location")]
+    pub location: (f64, f64),
+
+    pub rank: u32,
+    pub receiver: String,
+
+    // [dB] running mean of the normalized signal quality relative to the other receivers of the same transmissions
+    pub score: f64,
+    pub comparison_count: u32,
+}
+
+// mean difference of the normalized signal quality (receiver_a - receiver_b) of shared transmissions
+#[derive(Debug, Clone, Serialize)]
+pub struct SensitivityPair {
+    pub receiver_a: String,
+    pub receiver_b: String,
+    pub count: u32,
+    pub mean_difference: f64,
+}
+
+// ranking of the receivers of a region, best first
+#[derive(Debug, Clone, Serialize)]
+pub struct SensitivityContainer {
+    pub ts: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_location")]
+    pub location: (f64, f64),
+    pub ranks: Vec<SensitivityRank>,
+    pub pairs: Vec<SensitivityPair>,
+}
@@ -6,6 +6,8 @@ use ogn_parser::{Callsign, Timestamp};
 use rust_decimal::prelude::*;
 use serde::Serialize;
 
+use crate::containers::protocol::Protocol;
+
 #[derive(Debug, Clone, Serialize)]
 pub struct StatusContainer {
     // Fields from ServerResponseContainer
@@ -18,6 +20,7 @@ pub struct StatusContainer {
     pub src_call: Callsign,
     pub dst_call: Callsign,
     pub receiver: Option<Callsign>,
+    pub protocol: Protocol,
 
     // Fields from AprsStatus
     pub receiver_time: Option<Timestamp>,
@@ -53,6 +56,7 @@ impl StatusContainer {
 
         lp = lp.add_tag("src_call", self.src_call.to_string());
         lp = lp.add_tag("dst_call", self.dst_call.to_string());
+        lp = lp.add_tag("protocol", self.protocol.as_str());
         if let Some(receiver) = &self.receiver {
             lp = lp.add_tag("receiver", receiver.to_string());
         }
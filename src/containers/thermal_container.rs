@@ -0,0 +1,106 @@
+use std::time::UNIX_EPOCH;
+
+use chrono::prelude::*;
+use influxlp_tools::LineProtocol;
+use ogn_parser::Callsign;
+use serde::Serialize;
+
+use crate::containers::{
+    position_container::serialize_location,
+    units::{self, Units},
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ThermalContainer {
+    pub units: Units,
+
+    // Fields from AprsPacket
+    pub src_call: Callsign,
+
+    // Fields from the circling phase
+    pub start_ts: DateTime<Utc>,
+    pub end_ts: DateTime<Utc>,
+    pub duration: i64,
+    #[serde(serialize_with = "serialize_location")]
+    pub location: (f64, f64),
+    pub base_altitude: u32,
+    pub top_altitude: u32,
+    pub climb_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ThermalHotspot {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub top_altitude: u32,
+    pub climb_rate: f64,
+    pub count: u32,
+    pub last_ts: DateTime<Utc>,
+
+    // climb rate weighted by the age of the last thermal (1.0 = just now)
+    pub strength: f64,
+}
+
+// snapshot of all thermals in the recent past
+#[derive(Debug, Clone, Serialize)]
+pub struct ThermalMapContainer {
+    pub ts: DateTime<Utc>,
+    pub units: Units,
+    pub hotspots: Vec<ThermalHotspot>,
+}
+
+impl ThermalContainer {
+    // convert the altitudes and the climb rate from the OGN units to SI units
+    pub fn into_si(mut self) -> Self {
+        if self.units == Units::Si {
+            return self;
+        }
+
+        self.units = Units::Si;
+        self.base_altitude = units::feet_to_metres(self.base_altitude);
+        self.top_altitude = units::feet_to_metres(self.top_altitude);
+        self.climb_rate = units::fpm_to_mps(self.climb_rate);
+        self
+    }
+
+    pub fn to_ilp(&self) -> String {
+        let mut lp = LineProtocol::new("thermals");
+
+        lp = lp.add_tag("src_call", self.src_call.to_string());
+        lp = lp.add_tag("units", self.units.as_str());
+
+        lp = lp.add_field("end_ts", self.end_ts.to_rfc3339());
+        lp = lp.add_field("duration", self.duration);
+        lp = lp.add_field("latitude", self.location.1);
+        lp = lp.add_field("longitude", self.location.0);
+        lp = lp.add_field("base_altitude", self.base_altitude);
+        lp = lp.add_field("top_altitude", self.top_altitude);
+        lp = lp.add_field("climb_rate", self.climb_rate);
+
+        let lp = lp.with_timestamp(
+            self.start_ts
+                .signed_duration_since(DateTime::<Utc>::from(UNIX_EPOCH))
+                .num_nanoseconds()
+                .unwrap(),
+        );
+
+        lp.build().unwrap()
+    }
+}
+
+impl ThermalMapContainer {
+    // convert the altitudes, climb rates and strengths from the OGN units to SI units
+    pub fn into_si(mut self) -> Self {
+        if self.units == Units::Si {
+            return self;
+        }
+
+        self.units = Units::Si;
+        for hotspot in &mut self.hotspots {
+            hotspot.top_altitude = units::feet_to_metres(hotspot.top_altitude);
+            hotspot.climb_rate = units::fpm_to_mps(hotspot.climb_rate);
+            hotspot.strength = units::fpm_to_mps(hotspot.strength);
+        }
+        self
+    }
+}
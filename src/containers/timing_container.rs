@@ -0,0 +1,48 @@
+use std::time::UNIX_EPOCH;
+
+use chrono::prelude::*;
+use influxlp_tools::LineProtocol;
+use serde::Serialize;
+
+// delay between the receiver time and the ingest time of the positions of a receiver
+#[derive(Debug, Clone, Serialize)]
+pub struct TimingContainer {
+    pub ts: DateTime<Utc>,
+    pub receiver: String,
+    pub sample_count: u32,
+
+    // [s] percentiles of ts - receiver_ts
+    pub min_delay: f64,
+    pub p10_delay: f64,
+    pub median_delay: f64,
+    pub p90_delay: f64,
+
+    // [s] median delay relative to the median delay of all receivers, flagged if too large
+    pub clock_offset: f64,
+    pub bad_clock: bool,
+}
+
+impl TimingContainer {
+    pub fn to_ilp(&self) -> String {
+        let mut lp = LineProtocol::new("timings");
+
+        lp = lp.add_tag("receiver", self.receiver.to_owned());
+
+        lp = lp.add_field("sample_count", self.sample_count);
+        lp = lp.add_field("min_delay", self.min_delay);
+        lp = lp.add_field("p10_delay", self.p10_delay);
+        lp = lp.add_field("median_delay", self.median_delay);
+        lp = lp.add_field("p90_delay", self.p90_delay);
+        lp = lp.add_field("clock_offset", self.clock_offset);
+        lp = lp.add_field("bad_clock", self.bad_clock);
+
+        let lp = lp.with_timestamp(
+            self.ts
+                .signed_duration_since(DateTime::<Utc>::from(UNIX_EPOCH))
+                .num_nanoseconds()
+                .unwrap(),
+        );
+
+        lp.build().unwrap()
+    }
+}
@@ -0,0 +1,64 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+// conversion factors from the OGN (aviation) units to SI units
+pub const FOOT: f64 = 0.3048; // [m]
+const KNOT: f64 = 1.852; // [km/h]
+const FEET_PER_MINUTE: f64 = 0.00508; // [m/s]
+const ROTATION: u8 = 3; // [°/s] one rotation per 2 min
+
+// conversion factor from the APRS weather wind speed
+pub const MPH: f64 = 0.44704; // [m/s]
+
+// unit system of the output: aviation (as sent by OGN: ft, kt, ft/min, rot) or SI (m, km/h, m/s, °/s)
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Units {
+    #[default]
+    Aviation,
+    Si,
+}
+
+impl Units {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Units::Aviation => "aviation",
+            Units::Si => "si",
+        }
+    }
+}
+
+// [ft] -> [m]
+pub fn feet_to_metres(feet: u32) -> u32 {
+    (feet as f64 * FOOT).round() as u32
+}
+
+// [ft] -> [m] for heights which can be negative (e.g. altitude differences)
+pub fn feet_to_metres_signed(feet: i32) -> i32 {
+    (feet as f64 * FOOT).round() as i32
+}
+
+// [kt] -> [km/h]
+pub fn knots_to_kmh(knots: u16) -> u16 {
+    (knots as f64 * KNOT).round() as u16
+}
+
+// [m/s] -> [kt] for wind speeds
+pub fn mps_to_knots(mps: f64) -> f64 {
+    mps * 3.6 / KNOT
+}
+
+// [kt] -> [km/h] for wind speeds
+pub fn knots_to_kmh_f64(knots: f64) -> f64 {
+    knots * KNOT
+}
+
+// [ft/min] -> [m/s]
+pub fn fpm_to_mps(fpm: f64) -> f64 {
+    fpm * FEET_PER_MINUTE
+}
+
+// [rot] -> [°/s]
+pub fn rot_to_dps(rot: Decimal) -> Decimal {
+    rot * Decimal::from(ROTATION)
+}
@@ -0,0 +1,190 @@
+use std::time::UNIX_EPOCH;
+
+use chrono::prelude::*;
+use influxlp_tools::LineProtocol;
+use ogn_parser::{AprsPosition, Callsign, Timestamp};
+use serde::Serialize;
+
+use crate::containers::{
+    position_container::serialize_location,
+    protocol::Protocol,
+    units::{self, Units},
+};
+
+// conversion factor from APRS weather units
+const HUNDREDTH_INCH: f64 = 0.254; // [mm]
+
+// weather beacons have the APRS weather symbol or weather data (e.g. FANET weather stations)
+pub fn is_weather(position: &AprsPosition) -> bool {
+    let comment = &position.comment;
+    position.symbol_code == '_'
+        || comment.wind_direction.is_some()
+        || comment.temperature.is_some()
+        || comment.humidity.is_some()
+        || comment.barometric_pressure.is_some()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WeatherContainer {
+    // Fields from ServerResponseContainer
+    pub ts: DateTime<Utc>,
+    pub units: Units,
+    #[serde(skip_serializing)]
+    pub raw_message: String,
+    pub receiver_ts: Option<DateTime<Utc>>,
+
+    // Fields from AprsPacket
+    pub src_call: Callsign,
+    pub dst_call: Callsign,
+    pub receiver: Option<Callsign>,
+    pub protocol: Protocol,
+
+    // Fields from AprsPosition
+    pub receiver_time: Option<Timestamp>,
+    #[serde(serialize_with = "serialize_location")]
+    pub location: (f64, f64),
+
+    // Fields from PositionComment, converted to metric units
+    pub altitude: Option<u32>,            // as for positions
+    pub wind_direction: Option<u16>,      // [°]
+    pub wind_speed: Option<f64>,          // [m/s]
+    pub gust: Option<f64>,                // [m/s]
+    pub temperature: Option<f64>,         // [°C]
+    pub rainfall_1h: Option<f64>,         // [mm]
+    pub rainfall_24h: Option<f64>,        // [mm]
+    pub rainfall_midnight: Option<f64>,   // [mm]
+    pub humidity: Option<u8>,             // [%]
+    pub barometric_pressure: Option<f64>, // [hPa]
+    pub unparsed: Option<String>,
+}
+
+impl WeatherContainer {
+    pub fn new(
+        ts: DateTime<Utc>,
+        raw_message: String,
+        receiver_ts: Option<DateTime<Utc>>,
+        src_call: Callsign,
+        dst_call: Callsign,
+        receiver: Option<Callsign>,
+        position: AprsPosition,
+    ) -> Self {
+        let comment = position.comment;
+        WeatherContainer {
+            ts,
+            units: Units::Aviation,
+            raw_message,
+            receiver_ts,
+
+            src_call,
+            protocol: Protocol::from_dst_call(&dst_call.to_string()),
+            dst_call,
+            receiver,
+
+            receiver_time: position.timestamp,
+            location: (*position.longitude, *position.latitude),
+
+            altitude: comment.altitude,
+            wind_direction: comment.wind_direction,
+            wind_speed: comment.wind_speed.map(|speed| speed as f64 * units::MPH),
+            gust: comment.gust.map(|gust| gust as f64 * units::MPH),
+            temperature: comment
+                .temperature
+                .map(|fahrenheit| (fahrenheit as f64 - 32.0) * 5.0 / 9.0),
+            rainfall_1h: comment.rainfall_1h.map(|rain| rain as f64 * HUNDREDTH_INCH),
+            rainfall_24h: comment
+                .rainfall_24h
+                .map(|rain| rain as f64 * HUNDREDTH_INCH),
+            rainfall_midnight: comment
+                .rainfall_midnight
+                .map(|rain| rain as f64 * HUNDREDTH_INCH),
+            // "h00" means 100%
+            humidity: comment
+                .humidity
+                .map(|humidity| if humidity == 0 { 100 } else { humidity }),
+            barometric_pressure: comment
+                .barometric_pressure
+                .map(|pressure| pressure as f64 / 10.0),
+            unparsed: comment.unparsed,
+        }
+    }
+
+    // convert the altitude from the OGN units to SI units, the weather data is metric already
+    pub fn into_si(mut self) -> Self {
+        if self.units == Units::Si {
+            return self;
+        }
+
+        self.units = Units::Si;
+        self.altitude = self.altitude.map(units::feet_to_metres);
+        self
+    }
+
+    pub fn to_ilp(&self) -> String {
+        let mut lp = LineProtocol::new("weather");
+
+        lp = lp.add_tag("src_call", self.src_call.to_string());
+        lp = lp.add_tag("dst_call", self.dst_call.to_string());
+        lp = lp.add_tag("protocol", self.protocol.as_str());
+        lp = lp.add_tag("units", self.units.as_str());
+        if let Some(receiver) = &self.receiver {
+            lp = lp.add_tag("receiver", receiver.to_string());
+        }
+
+        // Fields from ServerResponseContainer
+        lp = lp.add_field("raw_message", self.raw_message.to_owned());
+        if let Some(ts) = self.receiver_ts {
+            lp = lp.add_field("receiver_ts", ts.to_rfc3339());
+        }
+
+        // Fields from AprsPosition
+        if let Some(receiver_time) = &self.receiver_time {
+            lp = lp.add_field("receiver_time", receiver_time.to_string());
+        }
+        lp = lp.add_field("latitude", self.location.1);
+        lp = lp.add_field("longitude", self.location.0);
+
+        // Fields from PositionComment
+        if let Some(altitude) = self.altitude {
+            lp = lp.add_field("altitude", altitude);
+        }
+        if let Some(wind_direction) = self.wind_direction {
+            lp = lp.add_field("wind_direction", wind_direction);
+        }
+        if let Some(wind_speed) = self.wind_speed {
+            lp = lp.add_field("wind_speed", wind_speed);
+        }
+        if let Some(gust) = self.gust {
+            lp = lp.add_field("gust", gust);
+        }
+        if let Some(temperature) = self.temperature {
+            lp = lp.add_field("temperature", temperature);
+        }
+        if let Some(rainfall_1h) = self.rainfall_1h {
+            lp = lp.add_field("rainfall_1h", rainfall_1h);
+        }
+        if let Some(rainfall_24h) = self.rainfall_24h {
+            lp = lp.add_field("rainfall_24h", rainfall_24h);
+        }
+        if let Some(rainfall_midnight) = self.rainfall_midnight {
+            lp = lp.add_field("rainfall_midnight", rainfall_midnight);
+        }
+        if let Some(humidity) = self.humidity {
+            lp = lp.add_field("humidity", humidity);
+        }
+        if let Some(barometric_pressure) = self.barometric_pressure {
+            lp = lp.add_field("barometric_pressure", barometric_pressure);
+        }
+        if let Some(unparsed) = &self.unparsed {
+            lp = lp.add_field("unparsed", unparsed);
+        }
+
+        let lp = lp.with_timestamp(
+            self.ts
+                .signed_duration_since(DateTime::<Utc>::from(UNIX_EPOCH))
+                .num_nanoseconds()
+                .unwrap(),
+        );
+
+        lp.build().unwrap()
+    }
+}
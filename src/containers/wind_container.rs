@@ -0,0 +1,71 @@
+use std::time::UNIX_EPOCH;
+
+use chrono::prelude::*;
+use influxlp_tools::LineProtocol;
+use serde::Serialize;
+
+use crate::containers::{
+    position_container::serialize_location,
+    units::{self, Units},
+};
+
+// wind estimate of a region and altitude band, averaged over circling aircraft and weather stations
+#[derive(Debug, Clone, Serialize)]
+pub struct WindContainer {
+    pub ts: DateTime<Utc>,
+    pub units: Units,
+
+    // centre of the region and lower bound of the altitude band
+    #[serde(serialize_with = "serialize_location")]
+    pub location: (f64, f64),
+    pub altitude_band: u32,
+
+    pub wind_direction: u16, // [°] where the wind comes from
+    pub wind_speed: f64,     // [kt]
+    pub gust: Option<f64>,   // [kt] weather stations only
+
+    // number of estimates from circling aircraft and from weather stations
+    pub circling_count: u32,
+    pub station_count: u32,
+}
+
+impl WindContainer {
+    // convert the altitude band and the wind speeds from the OGN units to SI units
+    pub fn into_si(mut self) -> Self {
+        if self.units == Units::Si {
+            return self;
+        }
+
+        self.units = Units::Si;
+        self.altitude_band = units::feet_to_metres(self.altitude_band);
+        self.wind_speed = units::knots_to_kmh_f64(self.wind_speed);
+        self.gust = self.gust.map(units::knots_to_kmh_f64);
+        self
+    }
+
+    pub fn to_ilp(&self) -> String {
+        let mut lp = LineProtocol::new("winds");
+
+        lp = lp.add_tag("latitude", self.location.1.to_string());
+        lp = lp.add_tag("longitude", self.location.0.to_string());
+        lp = lp.add_tag("altitude_band", self.altitude_band.to_string());
+        lp = lp.add_tag("units", self.units.as_str());
+
+        lp = lp.add_field("wind_direction", self.wind_direction);
+        lp = lp.add_field("wind_speed", self.wind_speed);
+        if let Some(gust) = self.gust {
+            lp = lp.add_field("gust", gust);
+        }
+        lp = lp.add_field("circling_count", self.circling_count);
+        lp = lp.add_field("station_count", self.station_count);
+
+        let lp = lp.with_timestamp(
+            self.ts
+                .signed_duration_since(DateTime::<Utc>::from(UNIX_EPOCH))
+                .num_nanoseconds()
+                .unwrap(),
+        );
+
+        lp.build().unwrap()
+    }
+}
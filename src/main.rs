@@ -5,23 +5,52 @@ extern crate ogn_parser;
 extern crate log;
 extern crate pretty_env_logger;
 
+mod airfields;
 mod containers;
 mod input;
 mod messages;
 mod output;
 mod processing;
+mod utils;
 
 use actix::*;
 use actix_ogn::OGNActor;
+use airfields::airfield_database::AirfieldDatabase;
 use clap::Parser;
+use containers::containers::Container;
+use containers::units::Units;
 use input::stdin_actor::StdinActor;
+use messages::server_response_container::ServerResponseContainer;
+use output::file_actor::FileActor;
 use output::influxdb_actor::InfluxDBActor;
 use output::postgresql_actor::PostgreSQLActor;
+use output::readsb_actor::ReadsbActor;
+use output::sbs_actor::SbsActor;
 use output::stdout_actor::StdoutActor;
+use output::webhook_actor::WebhookActor;
+use output::websocket_actor::WebSocketActor;
+use processing::airfield_actor::AirfieldActor;
+use processing::circling_actor::CirclingActor;
+use processing::coverage_actor::CoverageActor;
+use processing::decimation_actor::{DecimationActor, DecimationConfig};
+use processing::device_actor::DeviceActor;
+use processing::distributor_actor::{DistributorActor, Output, PositionStream};
 use processing::filter_actor::FilterActor;
+use processing::flight_actor::FlightActor;
+use processing::fusion_actor::FusionActor;
+use processing::health_actor::{HealthActor, HealthRule};
+use processing::inventory_actor::InventoryActor;
 use processing::parser_actor::ParserActor;
+use processing::polar_actor::PolarActor;
+use processing::qnh_actor::QnhActor;
+use processing::sensitivity_actor::SensitivityActor;
+use processing::state_actor::StateActor;
+use processing::thermal_actor::ThermalActor;
+use processing::timing_actor::TimingActor;
 use processing::validation_actor::ValidationActor;
-use std::collections::HashSet;
+use processing::wind_actor::WindActor;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
 #[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
 pub enum InputSource {
@@ -29,12 +58,17 @@ pub enum InputSource {
     Stdin,
 }
 
-#[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum OutputTarget {
     Stdout,
     PostgreSQL,
     InfluxDB,
     Mqtt,
+    Files,
+    Webhook,
+    WebSocket,
+    Readsb,
+    Sbs,
 }
 
 #[derive(Parser, Debug)]
@@ -44,9 +78,9 @@ struct Cli {
     #[arg(short, long, value_enum, default_value_t = InputSource::Glidernet)]
     source: InputSource,
 
-    /// specify output target
-    #[arg(short, long, value_enum, default_value_t = OutputTarget::Stdout)]
-    target: OutputTarget,
+    /// specify output targets (comma separated)
+    #[arg(short, long, value_enum, value_delimiter = ',', default_values_t = [OutputTarget::Stdout])]
+    target: Vec<OutputTarget>,
 
     /// maximum batch size for parallel stdin execution
     #[arg(short, long, default_value = "16384")]
@@ -60,6 +94,14 @@ struct Cli {
     )]
     database_url: String,
 
+    /// directory for snapshot files (e.g. the thermal map) of the files target
+    #[arg(long, default_value = ".")]
+    output_dir: String,
+
+    /// unit system of all outputs: aviation (ft, kt, ft/min, rot as sent by OGN) or si (m, km/h, m/s, °/s)
+    #[arg(long, value_enum, default_value_t = Units::Aviation)]
+    units: Units,
+
     /// MQTT host
     #[arg(long, default_value = "localhost")]
     mqtt_host: String,
@@ -68,6 +110,22 @@ struct Cli {
     #[arg(long, default_value_t = 1883)]
     mqtt_port: u16,
 
+    /// url of the webhook target, alerts are posted as JSON (plain http only)
+    #[arg(long, default_value = "http://localhost:8080/ogn")]
+    webhook_url: String,
+
+    /// address of the WebSocket target, positions and statuses are streamed as JSON
+    #[arg(long, default_value = "127.0.0.1:8001")]
+    websocket_address: String,
+
+    /// address of the SBS target, aircraft positions are served as SBS-1 BaseStation messages
+    #[arg(long, default_value = "127.0.0.1:30003")]
+    sbs_address: String,
+
+    /// serve also aircraft with non-ICAO address (e.g. FLARM, OGN) to the SBS target, their addresses may collide with ICAO addresses
+    #[arg(long)]
+    sbs_all_addresses: bool,
+
     /// let pass only packets with given destination callsigns (comma separated)
     #[arg(short, long)]
     included: Option<String>,
@@ -75,6 +133,130 @@ struct Cli {
     /// drop packets with given destination callsigns (comma separated)
     #[arg(short, long)]
     excluded: Option<String>,
+
+    /// let pass max. one raw position per sender and interval to a target, or earlier if it moved or turned (fused positions and derived data are not decimated): <TARGET>=<SECONDS>[,<METRES>[,<DEGREES>]]
+    #[arg(long, value_parser = parse_decimation)]
+    decimate: Vec<(OutputTarget, DecimationConfig)>,
+
+    /// select the position stream for a target, raw (every received copy), fused (one per transmission) or both: <TARGET>=<STREAM>
+    #[arg(long, value_parser = parse_position_stream)]
+    position_stream: Vec<(OutputTarget, PositionStream)>,
+
+    /// time window to collect copies of the same transmission from different receivers [s]
+    #[arg(long, default_value_t = 2)]
+    fusion_window: u64,
+
+    /// detect takeoffs and landings and write the flights (logbook) to the output targets
+    #[arg(long)]
+    flights: bool,
+
+    /// detect thermals from circling aircraft and keep a map of the recent thermals
+    #[arg(long)]
+    thermals: bool,
+
+    /// estimate the wind per region and altitude band from circling aircraft and weather stations
+    #[arg(long)]
+    wind: bool,
+
+    /// estimate the regional QNH from aircraft with GPS altitude and flight level, fill the altitude of aircraft with flight level only
+    #[arg(long)]
+    qnh: bool,
+
+    /// annotate positions near the ground with the nearest airfield from OurAirports (*.csv) or SeeYou (*.cup) files (comma separated)
+    #[arg(long, value_delimiter = ',')]
+    airfields: Vec<String>,
+
+    /// maximum distance of a position to the nearest airfield [m]
+    #[arg(long, default_value_t = 3000.0)]
+    airfield_distance: f64,
+
+    /// aggregate the received positions per receiver into a coverage grid
+    #[arg(long)]
+    coverage: bool,
+
+    /// size of the coverage grid cells [°]
+    #[arg(long, default_value_t = 0.05, value_parser = parse_resolution)]
+    coverage_resolution: f64,
+
+    /// build a polar diagram (range per bearing sector and altitude band) for every receiver
+    #[arg(long)]
+    polar: bool,
+
+    /// monitor the health of the receivers by their status beacons and send alerts
+    #[arg(long)]
+    health: bool,
+
+    /// rules for the receiver status (comma separated), e.g. "cpu_temperature>75,ram_free<20" [default: cpu_load>2,ram_free<20,cpu_temperature>75,ntp_offset>50,ntp_offset<-50,noise>10]
+    #[arg(long, value_delimiter = ',')]
+    health_rules: Vec<HealthRule>,
+
+    /// send an alert if a receiver is silent for this time [s]
+    #[arg(long, default_value_t = 900)]
+    health_gap: i64,
+
+    /// estimate the latency and clock offset of every receiver from the delay of its positions
+    #[arg(long)]
+    timing: bool,
+
+    /// rank the receivers per region by comparing the signal quality of transmissions received by several receivers
+    #[arg(long)]
+    sensitivity: bool,
+
+    /// keep an inventory of the receivers (location, version, platform, first and last seen) with their version history
+    #[arg(long)]
+    inventory: bool,
+
+    /// keep an inventory of the devices (firmware, hardware, receivers, ...) per address
+    #[arg(long)]
+    devices: bool,
+
+    /// keep the current picture of all aircraft (from the fused positions) and serve it as HTTP JSON API
+    #[arg(long)]
+    api: bool,
+
+    /// address of the HTTP JSON API
+    #[arg(long, default_value = "127.0.0.1:8000")]
+    api_address: String,
+
+    /// number of positions in the track history of every aircraft
+    #[arg(long, default_value_t = 60)]
+    track_length: usize,
+}
+
+fn parse_decimation(s: &str) -> Result<(OutputTarget, DecimationConfig), String> {
+    let (target, config) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected <TARGET>=<SECONDS>[,<METRES>[,<DEGREES>]], got '{s}'"))?;
+    let target = <OutputTarget as clap::ValueEnum>::from_str(target, true)?;
+    let config = config.parse::<DecimationConfig>()?;
+
+    Ok((target, config))
+}
+
+fn parse_position_stream(s: &str) -> Result<(OutputTarget, PositionStream), String> {
+    let (target, stream) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected <TARGET>=<STREAM>, got '{s}'"))?;
+    let target = <OutputTarget as clap::ValueEnum>::from_str(target, true)?;
+    let stream = <PositionStream as clap::ValueEnum>::from_str(stream, true)?;
+
+    Ok((target, stream))
+}
+
+fn parse_resolution(s: &str) -> Result<f64, String> {
+    let resolution = s.parse::<f64>().map_err(|err| err.to_string())?;
+    if !(resolution > 0.0 && resolution <= 90.0) {
+        return Err(format!("expected a cell size between 0 and 90°, got '{s}'"));
+    }
+
+    Ok(resolution)
+}
+
+fn recipients<A>(addr: Addr<A>) -> (Recipient<ServerResponseContainer>, Recipient<Container>)
+where
+    A: Actor<Context = Context<A>> + Handler<ServerResponseContainer> + Handler<Container>,
+{
+    (addr.clone().recipient(), addr.recipient())
 }
 
 fn main() {
@@ -84,11 +266,17 @@ fn main() {
     let cli = Cli::parse();
 
     let source = cli.source;
-    let target = cli.target;
+    let targets = cli.target;
     let database_url = cli.database_url;
     let batch_size = cli.batch_size;
     let mqtt_host = cli.mqtt_host;
     let mqtt_port = cli.mqtt_port;
+    let webhook_url = cli.webhook_url;
+    let websocket_address = cli.websocket_address;
+    let sbs_address = cli.sbs_address;
+    let sbs_all_addresses = cli.sbs_all_addresses;
+    let output_dir = cli.output_dir;
+    let units = cli.units;
     let included = cli.included.map(|s| {
         s.split(",")
             .map(|s| s.to_string())
@@ -99,37 +287,196 @@ fn main() {
             .map(|s| s.to_string())
             .collect::<HashSet<String>>()
     });
+    let decimations = cli.decimate.into_iter().collect::<HashMap<_, _>>();
+    let position_streams = cli.position_stream.into_iter().collect::<HashMap<_, _>>();
+    let fusion_window = Duration::from_secs(cli.fusion_window);
+    let flights = cli.flights;
+    let thermals = cli.thermals;
+    let wind = cli.wind;
+    let qnh = cli.qnh;
+    let mut airfield_database = AirfieldDatabase::new();
+    for path in &cli.airfields {
+        match airfield_database.load(path) {
+            Ok(count) => info!("Loaded {count} airfields from '{path}'"),
+            Err(err) => error!("{err}"),
+        }
+    }
+    let airfield_distance = cli.airfield_distance;
+    let coverage = cli.coverage;
+    let coverage_resolution = cli.coverage_resolution;
+    let polar = cli.polar;
+    let health = cli.health;
+    let health_rules = if cli.health_rules.is_empty() {
+        HealthRule::defaults()
+    } else {
+        cli.health_rules
+    };
+    let health_gap = cli.health_gap;
+    let timing = cli.timing;
+    let sensitivity = cli.sensitivity;
+    let inventory = cli.inventory;
+    let devices = cli.devices;
+    let api = cli.api;
+    let api_address = cli.api_address;
+    let track_length = cli.track_length;
 
     // The pipeline is as follows:
     // 1. Input source (yields raw OGN messages or OGN messages with timestamp)
     // 2. Parser actor (yields parsed data)
     // 3. Filter actor (filters the parsed data based on included/excluded destination callsigns)
     // 4. Validation actor (calculates additional data (e.g. distance, bearing, ...) and validates the parsed data)
-    // 5. Output target (writes the data to the chosen output target)
+    // 5. Processing actors (optional, derive data from several messages, e.g. flights, thermals, wind, QNH, coverage, polar diagrams, receiver health, timing, sensitivity, receiver and device inventory or fused positions)
+    // 6. Distributor actor (sends a copy of the data to every output target, optionally decimated per target)
+    // 7. Output targets (write the data to the chosen output targets)
 
     // Start actix
     let sys = actix::System::new("test");
 
-    // Connect the chosen output actor with the validation actor
-    let validator = match target {
-        OutputTarget::Stdout => {
-            let stdout = StdoutActor::new().start();
-            ValidationActor::new(stdout.recipient()).start()
-        }
-        OutputTarget::PostgreSQL => {
-            let postgresql = PostgreSQLActor::new(&database_url).start();
-            ValidationActor::new(postgresql.recipient()).start()
-        }
-        OutputTarget::InfluxDB => {
-            let influxdb = InfluxDBActor::new().start();
-            ValidationActor::new(influxdb.recipient()).start()
-        }
-        OutputTarget::Mqtt => {
-            let mqtt =
-                output::mqtt_actor::MqttActor::new("ogn-client-rs", &mqtt_host, mqtt_port).start();
-            ValidationActor::new(mqtt.recipient()).start()
-        }
-    };
+    // Start the chosen output actors, each with an optional decimation actor in front
+    let outputs = targets
+        .iter()
+        .map(|target| {
+            let position_stream = position_streams
+                .get(target)
+                .copied()
+                .unwrap_or(PositionStream::Raw);
+            let (recipient, container_recipient) = match target {
+                OutputTarget::Stdout => recipients(StdoutActor::new(units).start()),
+                OutputTarget::PostgreSQL => {
+                    recipients(PostgreSQLActor::new(&database_url, units).start())
+                }
+                OutputTarget::InfluxDB => recipients(InfluxDBActor::new(units).start()),
+                OutputTarget::Mqtt => recipients(
+                    output::mqtt_actor::MqttActor::new(
+                        "ogn-client-rs",
+                        &mqtt_host,
+                        mqtt_port,
+                        units,
+                    )
+                    .start(),
+                ),
+                OutputTarget::Files => recipients(FileActor::new(&output_dir, units).start()),
+                OutputTarget::Webhook => recipients(WebhookActor::new(&webhook_url).start()),
+                OutputTarget::Readsb => {
+                    recipients(ReadsbActor::new(&output_dir, position_stream).start())
+                }
+                OutputTarget::Sbs => {
+                    recipients(SbsActor::new(&sbs_address, sbs_all_addresses).start())
+                }
+                OutputTarget::WebSocket => {
+                    recipients(WebSocketActor::new(&websocket_address, units).start())
+                }
+            };
+
+            let recipient = match decimations.get(target) {
+                Some(config) => DecimationActor::new(recipient, config.clone())
+                    .start()
+                    .recipient(),
+                None => recipient,
+            };
+
+            Output {
+                recipient,
+                container_recipient,
+                position_stream,
+            }
+        })
+        .collect::<Vec<_>>();
+    let needs_fusion = outputs
+        .iter()
+        .any(|output| output.position_stream != PositionStream::Raw);
+
+    // Connect the processing actors (in reverse order) with the distributor actor
+    let distributor = DistributorActor::new(outputs).start();
+    let container_recipient: Recipient<Container> = distributor.clone().recipient();
+    let mut recipient: Recipient<ServerResponseContainer> = distributor.recipient();
+    // the state and sensitivity actors get the fused positions and pass them on to the distributor
+    let mut fused_recipient = container_recipient.clone();
+    if api {
+        fused_recipient = StateActor::new(fused_recipient, &api_address, track_length, units)
+            .start()
+            .recipient();
+    }
+    if sensitivity {
+        let sensitivity_actor = SensitivityActor::new(recipient, fused_recipient.clone()).start();
+        recipient = sensitivity_actor.clone().recipient();
+        fused_recipient = sensitivity_actor.recipient();
+    }
+    if needs_fusion || sensitivity || api {
+        recipient = FusionActor::new(recipient, fused_recipient, fusion_window)
+            .start()
+            .recipient();
+    }
+    // wind and thermals get the circling of the aircraft from the circling actor
+    let mut circling_subscribers = vec![];
+    if wind {
+        let wind_actor = WindActor::new(recipient, container_recipient.clone()).start();
+        recipient = wind_actor.clone().recipient();
+        circling_subscribers.push(wind_actor.recipient());
+    }
+    if thermals {
+        let thermal_actor = ThermalActor::new(recipient, container_recipient.clone()).start();
+        recipient = thermal_actor.clone().recipient();
+        circling_subscribers.push(thermal_actor.recipient());
+    }
+    if !circling_subscribers.is_empty() {
+        recipient = CirclingActor::new(recipient, circling_subscribers)
+            .start()
+            .recipient();
+    }
+    if flights {
+        recipient = FlightActor::new(recipient, container_recipient.clone())
+            .start()
+            .recipient();
+    }
+    if devices {
+        recipient = DeviceActor::new(recipient, container_recipient.clone())
+            .start()
+            .recipient();
+    }
+    if inventory {
+        recipient = InventoryActor::new(recipient, container_recipient.clone())
+            .start()
+            .recipient();
+    }
+    if timing {
+        recipient = TimingActor::new(recipient, container_recipient.clone())
+            .start()
+            .recipient();
+    }
+    if health {
+        recipient = HealthActor::new(
+            recipient,
+            container_recipient.clone(),
+            health_rules,
+            health_gap,
+        )
+        .start()
+        .recipient();
+    }
+    if polar {
+        recipient = PolarActor::new(recipient, container_recipient.clone())
+            .start()
+            .recipient();
+    }
+    if coverage {
+        recipient = CoverageActor::new(recipient, container_recipient.clone(), coverage_resolution)
+            .start()
+            .recipient();
+    }
+    if !airfield_database.is_empty() {
+        recipient = AirfieldActor::new(recipient, airfield_database, airfield_distance)
+            .start()
+            .recipient();
+    }
+    if qnh {
+        recipient = QnhActor::new(recipient, container_recipient.clone())
+            .start()
+            .recipient();
+    }
+
+    // Connect the processing actors with the validation actor
+    let validator = ValidationActor::new(recipient).start();
 
     // Connect the validation actor to the filter actor
     let filter = FilterActor::new(validator.recipient(), included, excluded).start();
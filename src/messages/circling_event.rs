@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use actix::prelude::*;
+use ogn_parser::Callsign;
+
+use crate::utils::circling::{Circle, Circling};
+
+// sent by the circling actor to the actors which need the circling of the aircraft (thermals, wind)
+#[derive(Message, Clone)]
+#[rtype(result = "()")]
+pub enum CirclingEvent {
+    // a full circle of an ongoing circling phase
+    Circle {
+        src_call: Callsign,
+        circle: Circle,
+    },
+
+    // the circling phase has ended (or the aircraft is not seen anymore)
+    Ended {
+        src_call: Callsign,
+        circling: Arc<Circling>,
+    },
+}
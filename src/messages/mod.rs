@@ -1,2 +1,3 @@
+pub mod circling_event;
 pub mod ognmessagewithtimestamp;
 pub mod server_response_container;
@@ -2,7 +2,9 @@ use actix::prelude::*;
 use chrono::prelude::*;
 use ogn_parser::ServerResponse;
 
-#[derive(Message)]
+use crate::{containers::units::FOOT, utils::geo};
+
+#[derive(Message, Clone)]
 #[rtype(result = "()")]
 pub struct ServerResponseContainer {
     pub server_response: ServerResponse,
@@ -17,7 +19,83 @@ pub struct ServerResponseContainer {
     // APRS positions may have a bearing and distance to the receiver
     pub bearing: Option<f64>,
     pub distance: Option<f64>,
+    pub receiver_altitude: Option<u32>,
+    pub slant_range: Option<f64>,
+    pub elevation_angle: Option<f64>,
+    pub altitude_difference: Option<i32>,
     pub normalized_signal_quality: Option<f64>,
 
     pub plausibility: Option<u16>,
+
+    // APRS positions near the ground may be at an airfield
+    pub airfield: Option<String>,
+    pub airfield_distance: Option<f64>,
+    pub airfield_elevation: Option<f64>,
+
+    // APRS positions with a flight level have a pressure altitude [ft], the altitude may be estimated from it with the regional QNH
+    pub pressure_altitude: Option<u32>,
+    pub qnh: Option<f64>,
+    pub altitude_estimated: Option<bool>,
+}
+
+impl ServerResponseContainer {
+    pub fn new(server_response: ServerResponse, ts: DateTime<Utc>, raw_message: String) -> Self {
+        ServerResponseContainer {
+            server_response,
+            ts,
+            raw_message,
+            receiver_ts: None,
+            bearing: None,
+            distance: None,
+            receiver_altitude: None,
+            slant_range: None,
+            elevation_angle: None,
+            altitude_difference: None,
+            normalized_signal_quality: None,
+            plausibility: None,
+            airfield: None,
+            airfield_distance: None,
+            airfield_elevation: None,
+            pressure_altitude: None,
+            qnh: None,
+            altitude_estimated: None,
+        }
+    }
+}
+
+// 3D geometry from the receiver to the sender and the signal quality normalized to 10km (needs both altitudes)
+pub struct ReceiverGeometry {
+    pub altitude_difference: Option<i32>,
+    pub slant_range: Option<f64>,
+    pub elevation_angle: Option<f64>,
+    pub normalized_signal_quality: Option<f64>,
+}
+
+impl ReceiverGeometry {
+    pub fn new(
+        distance: f64,
+        altitude: Option<u32>,
+        receiver_altitude: Option<u32>,
+        signal_quality: Option<f64>,
+    ) -> Self {
+        let altitude_difference = altitude
+            .zip(receiver_altitude)
+            .map(|(altitude, receiver_altitude)| altitude as i32 - receiver_altitude as i32);
+        let (slant_range, elevation_angle) = altitude_difference
+            .map(|altitude_difference| geo::slant(distance, altitude_difference as f64 * FOOT))
+            .unzip();
+        let normalized_signal_quality = signal_quality
+            .filter(|signal_quality| *signal_quality > 0.0)
+            .map(|signal_quality| {
+                let range = slant_range.unwrap_or(distance);
+                signal_quality + 20.0 * (range / 10_000.0).log10()
+            });
+
+        ReceiverGeometry {
+            altitude_difference,
+            slant_range,
+            elevation_angle,
+            normalized_signal_quality,
+        }
+    }
 }
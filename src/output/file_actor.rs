@@ -0,0 +1,102 @@
+use std::{fs, path::PathBuf};
+
+use actix::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    containers::{containers::Container, units::Units},
+    messages::server_response_container::ServerResponseContainer,
+};
+
+// writes snapshots (e.g. the thermal map) as files into the output directory, every snapshot replaces the previous one
+pub struct FileActor {
+    pub output_dir: PathBuf,
+    pub units: Units,
+}
+
+impl FileActor {
+    pub fn new(output_dir: &str, units: Units) -> Self {
+        FileActor {
+            output_dir: PathBuf::from(output_dir),
+            units,
+        }
+    }
+
+    fn write_json(&self, file_name: &str, content: &impl Serialize) {
+        match serde_json::to_string(content) {
+            Ok(json) => self.write(file_name, json.as_bytes()),
+            Err(err) => error!("Error serializing '{file_name}': {err}"),
+        }
+    }
+
+    // the receiver callsign comes from the network, so only [A-Za-z0-9_-] is kept for the file name
+    fn receiver_file_name(receiver: &str) -> String {
+        receiver
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
+
+    // write to a temporary file first, so readers never see a partially written file
+    fn write(&self, file_name: &str, content: &[u8]) {
+        let path = self.output_dir.join(file_name);
+        let tmp_path = self.output_dir.join(format!(".{file_name}.tmp"));
+        if let Err(err) = fs::write(&tmp_path, content).and_then(|_| fs::rename(&tmp_path, &path)) {
+            error!("Error writing '{}': {err}", path.display());
+        } else {
+            trace!("Written '{}'", path.display());
+        }
+    }
+}
+
+impl Actor for FileActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _: &mut Self::Context) {
+        info!("FileActor started");
+        if let Err(err) = fs::create_dir_all(&self.output_dir) {
+            error!(
+                "Error creating output directory '{}': {err}",
+                self.output_dir.display()
+            );
+        }
+    }
+}
+
+impl Handler<ServerResponseContainer> for FileActor {
+    type Result = ();
+
+    fn handle(&mut self, _: ServerResponseContainer, _: &mut Self::Context) {
+        // Single messages are not written to files, only snapshots
+    }
+}
+
+impl Handler<Container> for FileActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: Container, _: &mut Self::Context) {
+        match msg.with_units(self.units) {
+            Container::ThermalMap(thermal_map) => {
+                self.write_json("thermal_map.json", &thermal_map);
+            }
+            Container::Coverage(coverage) => {
+                self.write_json("coverage.geojson", &coverage.to_geojson());
+            }
+            Container::ReceiverInventory(inventory) => {
+                self.write_json("receivers.json", &inventory);
+            }
+            Container::Polar(polar) => {
+                let receiver = Self::receiver_file_name(&polar.receiver);
+                self.write_json(&format!("polar_{receiver}.json"), &polar);
+                self.write(&format!("polar_{receiver}.svg"), polar.to_svg().as_bytes());
+            }
+            _ => {}
+        }
+    }
+}
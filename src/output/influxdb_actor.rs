@@ -1,10 +1,13 @@
 use actix::prelude::*;
 
 use crate::{
-    containers::containers::Container, messages::server_response_container::ServerResponseContainer,
+    containers::{containers::Container, units::Units},
+    messages::server_response_container::ServerResponseContainer,
 };
 
-pub struct InfluxDBActor;
+pub struct InfluxDBActor {
+    pub units: Units,
+}
 
 impl Actor for InfluxDBActor {
     type Context = Context<Self>;
@@ -15,23 +18,51 @@ impl Actor for InfluxDBActor {
 }
 
 impl InfluxDBActor {
-    pub fn new() -> Self {
-        InfluxDBActor
+    pub fn new(units: Units) -> Self {
+        InfluxDBActor { units }
     }
 }
 
 impl Handler<ServerResponseContainer> for InfluxDBActor {
     type Result = ();
 
-    fn handle(&mut self, msg: ServerResponseContainer, _: &mut Self::Context) {
-        let container = msg.into();
-        match container {
+    fn handle(&mut self, msg: ServerResponseContainer, ctx: &mut Self::Context) {
+        <Self as Handler<Container>>::handle(self, msg.into(), ctx);
+    }
+}
+
+impl Handler<Container> for InfluxDBActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: Container, _: &mut Self::Context) {
+        match msg.with_units(self.units) {
             Container::Position(position) => {
                 println!("{}", position.to_ilp());
             }
             Container::Status(status) => {
                 println!("{}", status.to_ilp());
             }
+            Container::Weather(weather) => {
+                println!("{}", weather.to_ilp());
+            }
+            Container::FusedPosition(fused_position) => {
+                println!("{}", fused_position.to_ilp());
+            }
+            Container::Flight(flight) => {
+                println!("{}", flight.to_ilp());
+            }
+            Container::Thermal(thermal) => {
+                println!("{}", thermal.to_ilp());
+            }
+            Container::Wind(wind) => {
+                println!("{}", wind.to_ilp());
+            }
+            Container::Qnh(qnh) => {
+                println!("{}", qnh.to_ilp());
+            }
+            Container::Timing(timing) => {
+                println!("{}", timing.to_ilp());
+            }
             _ => {
                 // For now, just print the message
                 //println!("Received container: {:?}", container);
@@ -1,4 +1,9 @@
+pub mod file_actor;
 pub mod influxdb_actor;
 pub mod mqtt_actor;
 pub mod postgresql_actor;
+pub mod readsb_actor;
+pub mod sbs_actor;
 pub mod stdout_actor;
+pub mod webhook_actor;
+pub mod websocket_actor;
@@ -2,15 +2,20 @@ use std::time::Duration;
 
 use actix::prelude::*;
 use rumqttc::{Client, MqttOptions};
+use serde_json::json;
 
-use crate::messages::server_response_container::ServerResponseContainer;
+use crate::{
+    containers::{containers::Container, units::Units},
+    messages::server_response_container::ServerResponseContainer,
+};
 
 pub struct MqttActor {
     client: Client,
+    units: Units,
 }
 
 impl MqttActor {
-    pub fn new(id: &str, host: &str, port: u16) -> Self {
+    pub fn new(id: &str, host: &str, port: u16, units: Units) -> Self {
         let mut options = MqttOptions::new(id, host, port);
         options.set_keep_alive(Duration::from_secs(5));
 
@@ -23,7 +28,26 @@ impl MqttActor {
             }
         });
 
-        MqttActor { client }
+        MqttActor { client, units }
+    }
+
+    fn publish(&mut self, topic: &str, payload: String) {
+        self.publish_with_retain(topic, payload, false);
+    }
+
+    // retained messages are delivered to new subscribers, e.g. the last snapshot of a map
+    fn publish_with_retain(&mut self, topic: &str, payload: String, retain: bool) {
+        match self
+            .client
+            .publish(topic, rumqttc::QoS::AtLeastOnce, retain, payload.clone())
+        {
+            Ok(_) => {
+                trace!("Published MQTT message to topic '{}': {}", topic, payload);
+            }
+            Err(e) => {
+                error!("Failed to publish MQTT message: {}", e);
+            }
+        }
     }
 }
 
@@ -38,29 +62,84 @@ impl Actor for MqttActor {
 impl Handler<ServerResponseContainer> for MqttActor {
     type Result = ();
 
-    fn handle(&mut self, msg: ServerResponseContainer, _: &mut Self::Context) {
-        let container = msg.into();
-        match container {
-            // Currently, we only handle Position containers for MQTT
-            crate::containers::containers::Container::Position(position) => {
+    fn handle(&mut self, msg: ServerResponseContainer, ctx: &mut Self::Context) {
+        <Self as Handler<Container>>::handle(self, msg.into(), ctx);
+    }
+}
+
+impl Handler<Container> for MqttActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: Container, _: &mut Self::Context) {
+        match msg.with_units(self.units) {
+            Container::Position(position) => {
                 if let (Some(receiver), Some(distance)) = (position.receiver, position.distance) {
                     let topic = format!("ogn/{}/{}", receiver, position.src_call);
-                    let payload = distance.to_string();
-                    match self.client.publish(
-                        &topic,
-                        rumqttc::QoS::AtLeastOnce,
-                        false,
-                        payload.clone(),
-                    ) {
-                        Ok(_) => {
-                            trace!("Published MQTT message to topic '{}': {}", &topic, payload);
-                        }
-                        Err(e) => {
-                            error!("Failed to publish MQTT message: {}", e);
-                        }
-                    }
+                    let payload = json!({
+                        "distance": distance,
+                        "aircraft_type_name": position.aircraft_type_name,
+                        "address_type_name": position.address_type_name,
+                    });
+                    self.publish(&topic, payload.to_string());
                 }
             }
+            Container::Weather(weather) => {
+                let topic = format!("ogn/weather/{}", weather.src_call);
+                self.publish(&topic, serde_json::to_string(&weather).unwrap());
+            }
+            Container::FusedPosition(fused_position) => {
+                let topic = format!("ogn/fused/{}", fused_position.src_call);
+                self.publish(&topic, serde_json::to_string(&fused_position).unwrap());
+            }
+            Container::Flight(flight) => {
+                let topic = format!("ogn/flights/{}", flight.src_call);
+                self.publish(&topic, serde_json::to_string(&flight).unwrap());
+            }
+            Container::Thermal(thermal) => {
+                let topic = format!("ogn/thermals/{}", thermal.src_call);
+                self.publish(&topic, serde_json::to_string(&thermal).unwrap());
+            }
+            Container::ThermalMap(thermal_map) => {
+                self.publish_with_retain(
+                    "ogn/thermal_map",
+                    serde_json::to_string(&thermal_map).unwrap(),
+                    true,
+                );
+            }
+            Container::Wind(wind) => {
+                let topic = format!(
+                    "ogn/wind/{:.2}/{:.2}/{}",
+                    wind.location.1, wind.location.0, wind.altitude_band
+                );
+                self.publish_with_retain(&topic, serde_json::to_string(&wind).unwrap(), true);
+            }
+            Container::Qnh(qnh) => {
+                let topic = format!("ogn/qnh/{:.2}/{:.2}", qnh.location.1, qnh.location.0);
+                self.publish_with_retain(&topic, serde_json::to_string(&qnh).unwrap(), true);
+            }
+            Container::Polar(polar) => {
+                let topic = format!("ogn/polar/{}", polar.receiver);
+                self.publish_with_retain(&topic, serde_json::to_string(&polar).unwrap(), true);
+            }
+            Container::Alert(alert) => {
+                let topic = format!("ogn/alerts/{}", alert.receiver);
+                self.publish(&topic, serde_json::to_string(&alert).unwrap());
+            }
+            Container::Timing(timing) => {
+                let topic = format!("ogn/timing/{}", timing.receiver);
+                self.publish_with_retain(&topic, serde_json::to_string(&timing).unwrap(), true);
+            }
+            Container::Sensitivity(sensitivity) => {
+                let topic = format!(
+                    "ogn/sensitivity/{:.2}/{:.2}",
+                    sensitivity.location.1, sensitivity.location.0
+                );
+                self.publish_with_retain(
+                    &topic,
+                    serde_json::to_string(&sensitivity).unwrap(),
+                    true,
+                );
+            }
             _ => {
                 // For now, we ignore other container types
             }
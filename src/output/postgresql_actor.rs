@@ -6,31 +6,175 @@ use postgres::{Client, NoTls};
 
 use crate::{
     containers::{
-        containers::Container, parser_error_container::ParserErrorContainer,
-        position_container::PositionContainer, server_comment_container::ServerCommentContainer,
+        alert_container::AlertContainer,
+        containers::Container,
+        coverage_container::CoverageCell,
+        device_container::DeviceInventory,
+        flight_container::FlightContainer,
+        fused_position_container::FusedPositionContainer,
+        parser_error_container::ParserErrorContainer,
+        polar_container::PolarSector,
+        position_container::PositionContainer,
+        qnh_container::QnhContainer,
+        receiver_inventory_container::{ReceiverInventory, ReceiverVersionContainer},
+        sensitivity_container::SensitivityRank,
+        server_comment_container::ServerCommentContainer,
         status_container::StatusContainer,
+        thermal_container::ThermalContainer,
+        timing_container::TimingContainer,
+        units::Units,
+        weather_container::WeatherContainer,
+        wind_container::WindContainer,
     },
     messages::server_response_container::ServerResponseContainer,
 };
 
+// how an upsert updates a column of an existing row (other columns are overwritten)
+#[derive(Debug, Clone, Copy)]
+enum ColumnUpdate {
+    // written only for new rows (e.g. "first_seen")
+    Keep,
+    // overwritten only with known values, e.g. the version of a receiver which has not sent a status since the start
+    Coalesce,
+    // merged with the existing value by an SQL expression, e.g. counters which are summed up
+    Merge(&'static str),
+}
+
+// statistics of the devices are sent since the last inventory, so they are added to the existing ones
+const DEVICE_UPDATES: &[(&str, ColumnUpdate)] = &[
+    ("first_seen", ColumnUpdate::Keep),
+    ("address_type_name", ColumnUpdate::Coalesce),
+    ("software_version", ColumnUpdate::Coalesce),
+    ("hardware_version", ColumnUpdate::Coalesce),
+    ("original_address", ColumnUpdate::Coalesce),
+    (
+        "last_seen",
+        ColumnUpdate::Merge("GREATEST(devices.last_seen, EXCLUDED.last_seen)"),
+    ),
+    (
+        "message_count",
+        ColumnUpdate::Merge("COALESCE(devices.message_count, 0) + EXCLUDED.message_count"),
+    ),
+    (
+        "frequency_offset_count",
+        ColumnUpdate::Merge(
+            "COALESCE(devices.frequency_offset_count, 0) + EXCLUDED.frequency_offset_count",
+        ),
+    ),
+    (
+        "mean_frequency_offset",
+        ColumnUpdate::Merge(
+            "COALESCE((COALESCE(devices.mean_frequency_offset * devices.frequency_offset_count, 0) \
+            + COALESCE(EXCLUDED.mean_frequency_offset * EXCLUDED.frequency_offset_count, 0)) \
+            / NULLIF(COALESCE(devices.frequency_offset_count, 0) + EXCLUDED.frequency_offset_count, 0), \
+            devices.mean_frequency_offset)",
+        ),
+    ),
+    (
+        "receiver_count",
+        ColumnUpdate::Merge(
+            "(SELECT COUNT(DISTINCT receiver) FROM jsonb_array_elements_text(\
+            COALESCE(devices.receivers, '[]') || EXCLUDED.receivers) AS receiver)",
+        ),
+    ),
+    (
+        "receivers",
+        ColumnUpdate::Merge(
+            "(SELECT jsonb_agg(DISTINCT receiver ORDER BY receiver) FROM jsonb_array_elements_text(\
+            COALESCE(devices.receivers, '[]') || EXCLUDED.receivers) AS receiver)",
+        ),
+    ),
+];
+
+// coverage cells are sent with the positions since the last snapshot, so they are merged with the stored statistics
+const COVERAGE_UPDATES: &[(&str, ColumnUpdate)] = &[
+    (
+        "message_count",
+        ColumnUpdate::Merge("COALESCE(coverage.message_count, 0) + EXCLUDED.message_count"),
+    ),
+    (
+        "quality_count",
+        ColumnUpdate::Merge("COALESCE(coverage.quality_count, 0) + EXCLUDED.quality_count"),
+    ),
+    (
+        "max_normalized_quality",
+        ColumnUpdate::Merge(
+            "GREATEST(coverage.max_normalized_quality, EXCLUDED.max_normalized_quality)",
+        ),
+    ),
+    (
+        "mean_normalized_quality",
+        ColumnUpdate::Merge(
+            "COALESCE((COALESCE(coverage.mean_normalized_quality * coverage.quality_count, 0) \
+            + COALESCE(EXCLUDED.mean_normalized_quality * EXCLUDED.quality_count, 0)) \
+            / NULLIF(COALESCE(coverage.quality_count, 0) + EXCLUDED.quality_count, 0), \
+            coverage.mean_normalized_quality)",
+        ),
+    ),
+    (
+        "min_altitude",
+        ColumnUpdate::Merge("LEAST(coverage.min_altitude, EXCLUDED.min_altitude)"),
+    ),
+    (
+        "max_distance",
+        ColumnUpdate::Merge("GREATEST(coverage.max_distance, EXCLUDED.max_distance)"),
+    ),
+];
+
+// polar diagrams only cover the sectors since the start of the client, so a shorter range doesn't replace the stored one
+const POLAR_UPDATES: &[(&str, ColumnUpdate)] = &[(
+    "max_range",
+    ColumnUpdate::Merge("GREATEST(polars.max_range, EXCLUDED.max_range)"),
+)];
+
 pub struct PostgreSQLActor {
     pub client: Option<postgres::Client>,
+    pub units: Units,
 
     pub position_containers: Vec<PositionContainer>,
     pub status_containers: Vec<StatusContainer>,
     pub server_comment_containers: Vec<ServerCommentContainer>,
     pub parser_error_containers: Vec<ParserErrorContainer>,
+    pub weather_containers: Vec<WeatherContainer>,
+    pub fused_position_containers: Vec<FusedPositionContainer>,
+    pub flight_containers: Vec<FlightContainer>,
+    pub thermal_containers: Vec<ThermalContainer>,
+    pub wind_containers: Vec<WindContainer>,
+    pub qnh_containers: Vec<QnhContainer>,
+    pub coverage_cells: Vec<CoverageCell>,
+    pub polar_sectors: Vec<PolarSector>,
+    pub alert_containers: Vec<AlertContainer>,
+    pub timing_containers: Vec<TimingContainer>,
+    pub sensitivity_ranks: Vec<SensitivityRank>,
+    pub receiver_inventories: Vec<ReceiverInventory>,
+    pub receiver_version_containers: Vec<ReceiverVersionContainer>,
+    pub device_inventories: Vec<DeviceInventory>,
 }
 
 impl PostgreSQLActor {
-    pub fn new(database_url: &str) -> Self {
+    pub fn new(database_url: &str, units: Units) -> Self {
         PostgreSQLActor {
             client: Client::connect(database_url, NoTls).ok(),
+            units,
 
             position_containers: vec![],
             status_containers: vec![],
             server_comment_containers: vec![],
             parser_error_containers: vec![],
+            weather_containers: vec![],
+            fused_position_containers: vec![],
+            flight_containers: vec![],
+            thermal_containers: vec![],
+            wind_containers: vec![],
+            qnh_containers: vec![],
+            coverage_cells: vec![],
+            polar_sectors: vec![],
+            alert_containers: vec![],
+            timing_containers: vec![],
+            sensitivity_ranks: vec![],
+            receiver_inventories: vec![],
+            receiver_version_containers: vec![],
+            device_inventories: vec![],
         }
     }
 
@@ -83,6 +227,130 @@ impl PostgreSQLActor {
             self.insert_into_db("errors", &header, &body);
             self.parser_error_containers.clear();
         }
+
+        if !self.weather_containers.is_empty() {
+            let (header, body) = self.get_header_and_body(&self.weather_containers);
+            self.insert_into_db("weather", &header, &body);
+            self.weather_containers.clear();
+        }
+
+        if !self.fused_position_containers.is_empty() {
+            let rows = self
+                .fused_position_containers
+                .iter()
+                .map(|fused_position| fused_position.to_row())
+                .collect::<Vec<_>>();
+            let (header, body) = self.get_header_and_body(&rows);
+            self.insert_into_db("fused_positions", &header, &body);
+            self.fused_position_containers.clear();
+        }
+
+        if !self.flight_containers.is_empty() {
+            let (header, body) = self.get_header_and_body(&self.flight_containers);
+            self.insert_into_db("flights", &header, &body);
+            self.flight_containers.clear();
+        }
+
+        if !self.thermal_containers.is_empty() {
+            let (header, body) = self.get_header_and_body(&self.thermal_containers);
+            self.insert_into_db("thermals", &header, &body);
+            self.thermal_containers.clear();
+        }
+
+        if !self.wind_containers.is_empty() {
+            let (header, body) = self.get_header_and_body(&self.wind_containers);
+            self.insert_into_db("winds", &header, &body);
+            self.wind_containers.clear();
+        }
+
+        if !self.qnh_containers.is_empty() {
+            let (header, body) = self.get_header_and_body(&self.qnh_containers);
+            self.insert_into_db("qnh", &header, &body);
+            self.qnh_containers.clear();
+        }
+
+        if !self.coverage_cells.is_empty() {
+            let rows = self
+                .coverage_cells
+                .iter()
+                .map(|cell| cell.to_row())
+                .collect::<Vec<_>>();
+            let (header, body) = self.get_header_and_body(&rows);
+            self.upsert_into_db(
+                "coverage",
+                &["receiver", "location", "resolution"],
+                COVERAGE_UPDATES,
+                &header,
+                &body,
+            );
+            self.coverage_cells.clear();
+        }
+
+        if !self.polar_sectors.is_empty() {
+            let (header, body) = self.get_header_and_body(&self.polar_sectors);
+            self.upsert_into_db(
+                "polars",
+                &["receiver", "bearing", "altitude_band"],
+                POLAR_UPDATES,
+                &header,
+                &body,
+            );
+            self.polar_sectors.clear();
+        }
+
+        if !self.alert_containers.is_empty() {
+            let (header, body) = self.get_header_and_body(&self.alert_containers);
+            self.insert_into_db("alerts", &header, &body);
+            self.alert_containers.clear();
+        }
+
+        if !self.timing_containers.is_empty() {
+            let (header, body) = self.get_header_and_body(&self.timing_containers);
+            self.insert_into_db("timings", &header, &body);
+            self.timing_containers.clear();
+        }
+
+        if !self.sensitivity_ranks.is_empty() {
+            let (header, body) = self.get_header_and_body(&self.sensitivity_ranks);
+            self.insert_into_db("sensitivities", &header, &body);
+            self.sensitivity_ranks.clear();
+        }
+
+        if !self.receiver_inventories.is_empty() {
+            let (header, body) = self.get_header_and_body(&self.receiver_inventories);
+            self.upsert_into_db(
+                "receivers",
+                &["receiver"],
+                &[
+                    ("first_seen", ColumnUpdate::Keep),
+                    ("location", ColumnUpdate::Coalesce),
+                    ("altitude", ColumnUpdate::Coalesce),
+                    ("version", ColumnUpdate::Coalesce),
+                    ("platform", ColumnUpdate::Coalesce),
+                ],
+                &header,
+                &body,
+            );
+            self.receiver_inventories.clear();
+        }
+
+        if !self.receiver_version_containers.is_empty() {
+            let (header, body) = self.get_header_and_body(&self.receiver_version_containers);
+            self.insert_into_db("receiver_versions", &header, &body);
+            self.receiver_version_containers.clear();
+        }
+
+        if !self.device_inventories.is_empty() {
+            let (header, body) = self.get_header_and_body(&self.device_inventories);
+            self.upsert_into_db(
+                "devices",
+                &["address_type", "address"],
+                DEVICE_UPDATES,
+                &header,
+                &body,
+            );
+            self.device_inventories.clear();
+        }
     }
 
     fn insert_into_db(&mut self, table_name: &str, header: &str, body: &[u8]) {
@@ -99,6 +367,61 @@ impl PostgreSQLActor {
             Err(err) => error!("Error: {err}\nTable: {table_name}\nRows: {body:#?}"),
         };
     }
+
+    // insert new rows and update existing rows (identified by the key columns): COPY into a temporary table first,
+    // then INSERT ... ON CONFLICT. The columns of existing rows are overwritten unless there is a column update for them.
+    fn upsert_into_db(
+        &mut self,
+        table_name: &str,
+        key_columns: &[&str],
+        column_updates: &[(&str, ColumnUpdate)],
+        header: &str,
+        body: &[u8],
+    ) {
+        let client = self.client.as_mut().unwrap();
+        let updates = header
+            .split(',')
+            .filter(|column| !key_columns.contains(column))
+            .filter_map(|column| {
+                let update = column_updates
+                    .iter()
+                    .find(|(name, _)| *name == column)
+                    .map(|(_, update)| *update);
+                match update {
+                    None => Some(format!("{column} = EXCLUDED.{column}")),
+                    Some(ColumnUpdate::Keep) => None,
+                    Some(ColumnUpdate::Coalesce) => Some(format!(
+                        "{column} = COALESCE(EXCLUDED.{column}, {table_name}.{column})"
+                    )),
+                    Some(ColumnUpdate::Merge(expression)) => {
+                        Some(format!("{column} = {expression}"))
+                    }
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql_create = format!(
+            "CREATE TEMP TABLE tmp_{table_name} (LIKE {table_name} INCLUDING DEFAULTS) ON COMMIT DROP"
+        );
+        let sql_header = format!("COPY tmp_{table_name} ({header}) FROM STDIN WITH (FORMAT CSV)");
+        let sql_upsert = format!(
+            "INSERT INTO {table_name} ({header}) SELECT {header} FROM tmp_{table_name} ON CONFLICT ({}) DO UPDATE SET {updates}",
+            key_columns.join(", ")
+        );
+
+        let result = client.transaction().and_then(|mut transaction| {
+            transaction.batch_execute(&sql_create)?;
+            let mut copy_stdin = transaction.copy_in(&sql_header)?;
+            copy_stdin.write_all(body).unwrap();
+            copy_stdin.finish()?;
+            transaction.batch_execute(&sql_upsert)?;
+            transaction.commit()
+        });
+        match result {
+            Ok(_) => trace!("Rows upserted into table '{table_name}'"),
+            Err(err) => error!("Error: {err}\nTable: {table_name}\nRows: {body:#?}"),
+        };
+    }
 }
 
 impl Actor for PostgreSQLActor {
@@ -115,8 +438,16 @@ impl Actor for PostgreSQLActor {
 impl Handler<ServerResponseContainer> for PostgreSQLActor {
     type Result = ();
 
-    fn handle(&mut self, msg: ServerResponseContainer, _: &mut Self::Context) {
-        match msg.into() {
+    fn handle(&mut self, msg: ServerResponseContainer, ctx: &mut Self::Context) {
+        <Self as Handler<Container>>::handle(self, msg.into(), ctx);
+    }
+}
+
+impl Handler<Container> for PostgreSQLActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: Container, _: &mut Self::Context) {
+        match msg.with_units(self.units) {
             Container::Position(position) => {
                 self.position_containers.push(position);
             }
@@ -135,6 +466,60 @@ impl Handler<ServerResponseContainer> for PostgreSQLActor {
                     comment_container.raw_message
                 );
             }
+            Container::Weather(weather) => {
+                self.weather_containers.push(weather);
+            }
+            Container::FusedPosition(fused_position) => {
+                self.fused_position_containers.push(fused_position);
+            }
+            Container::Flight(flight) => {
+                self.flight_containers.push(flight);
+            }
+            Container::Thermal(thermal) => {
+                self.thermal_containers.push(thermal);
+            }
+            Container::Wind(wind) => {
+                self.wind_containers.push(wind);
+            }
+            Container::Qnh(qnh) => {
+                self.qnh_containers.push(qnh);
+            }
+            Container::Coverage(coverage) => {
+                // every snapshot has all cells, only the cells with new positions are written
+                self.coverage_cells = coverage
+                    .cells
+                    .into_iter()
+                    .filter(|cell| cell.new_message_count > 0)
+                    .collect();
+            }
+            Container::Polar(polar) => {
+                // every diagram has all sectors of the receiver, so only the last one is written
+                self.polar_sectors
+                    .retain(|sector| sector.receiver != polar.receiver);
+                self.polar_sectors.extend(polar.sectors);
+            }
+            Container::Alert(alert) => {
+                self.alert_containers.push(alert);
+            }
+            Container::Timing(timing) => {
+                self.timing_containers.push(timing);
+            }
+            Container::Sensitivity(sensitivity) => {
+                // the pairwise comparison is for live use only
+                self.sensitivity_ranks.extend(sensitivity.ranks);
+            }
+            Container::ReceiverInventory(inventory) => {
+                self.receiver_inventories = inventory.receivers;
+            }
+            Container::ReceiverVersion(version) => {
+                self.receiver_version_containers.push(version);
+            }
+            Container::DeviceInventory(inventory) => {
+                self.device_inventories.extend(inventory.devices);
+            }
+            Container::ThermalMap(_) => {
+                // The thermal map is a snapshot for live use, the thermals are stored already
+            }
         }
     }
 }
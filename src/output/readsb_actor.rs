@@ -0,0 +1,289 @@
+use std::{collections::HashMap, fs, path::PathBuf, time::Duration};
+
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    containers::{
+        address_type::AddressType, aircraft_type::AircraftType, containers::Container,
+        fused_position_container::FusedPositionContainer, position_container::PositionContainer,
+        protocol::Protocol,
+    },
+    messages::server_response_container::ServerResponseContainer,
+    processing::distributor_actor::PositionStream,
+    utils::icao,
+};
+
+const WRITE_INTERVAL: u64 = 1; // [s] like readsb
+const AIRCRAFT_LIFETIME: i64 = 300; // [s] aircraft not seen for this time are removed
+
+// aircraft in the readsb format (always aviation units: ft, kt, ft/min)
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadsbAircraft {
+    pub hex: String,
+    #[serde(rename = "type")]
+    pub source_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flight: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt_baro: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt_geom: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gs: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geom_rate: Option<i64>,
+    pub lat: f64,
+    pub lon: f64,
+    pub seen_pos: f64,
+    pub seen: f64,
+    pub messages: u64,
+
+    #[serde(skip_serializing)]
+    pub ts: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadsbAircraftJson<'a> {
+    pub now: f64,
+    pub messages: u64,
+    pub aircraft: Vec<&'a ReadsbAircraft>,
+}
+
+// ICAO addresses are plain hex, other addresses are marked with '~' (like non-ICAO addresses in readsb)
+fn hex(address: u32, address_type_name: Option<AddressType>) -> String {
+    match address_type_name {
+        Some(AddressType::Icao) => format!("{address:06x}"),
+        _ => format!("~{address:06x}"),
+    }
+}
+
+// ADS-B emitter category
+fn category(aircraft_type_name: Option<AircraftType>) -> Option<&'static str> {
+    match aircraft_type_name? {
+        AircraftType::Glider => Some("B1"),
+        AircraftType::Balloon | AircraftType::Airship => Some("B2"),
+        AircraftType::Skydiver => Some("B3"),
+        AircraftType::HangGlider | AircraftType::Paraglider => Some("B4"),
+        AircraftType::Uav => Some("B6"),
+        AircraftType::Helicopter => Some("A7"),
+        AircraftType::TowPlane | AircraftType::DropPlane | AircraftType::PoweredAircraft => {
+            Some("A1")
+        }
+        AircraftType::JetAircraft => Some("A3"),
+        AircraftType::StaticObject => Some("C3"),
+        AircraftType::Unknown | AircraftType::Reserved => None,
+    }
+}
+
+fn source_type(protocol: Protocol) -> &'static str {
+    match protocol {
+        Protocol::AdsB => "adsb_icao",
+        _ => "other",
+    }
+}
+
+impl ReadsbAircraft {
+    // aircraft without an address are skipped, readsb needs a hex address
+    fn from_position(position: &PositionContainer) -> Option<Self> {
+        Some(ReadsbAircraft {
+            hex: hex(position.address?, position.address_type_name),
+            source_type: source_type(position.protocol),
+            flight: None,
+            r: position.registration.clone(),
+            category: category(position.aircraft_type_name),
+            alt_baro: position
+                .flight_level
+                .and_then(|flight_level| (flight_level * Decimal::ONE_HUNDRED).to_i64()),
+            alt_geom: position.altitude,
+            gs: position.speed,
+            track: position.course,
+            geom_rate: position
+                .climb_rate
+                .map(|climb_rate| climb_rate.round() as i64),
+            lat: position.location.1,
+            lon: position.location.0,
+            seen_pos: 0.0,
+            seen: 0.0,
+            messages: 0,
+            ts: position.ts,
+        })
+    }
+
+    fn from_fused_position(fused_position: &FusedPositionContainer) -> Option<Self> {
+        let icao_address = fused_position
+            .address
+            .filter(|_| fused_position.address_type_name == Some(AddressType::Icao));
+        Some(ReadsbAircraft {
+            hex: hex(fused_position.address?, fused_position.address_type_name),
+            source_type: source_type(fused_position.protocol),
+            flight: None,
+            r: icao_address.and_then(icao::n_number),
+            category: category(fused_position.aircraft_type_name),
+            alt_baro: None,
+            alt_geom: fused_position.altitude,
+            gs: fused_position.speed,
+            track: fused_position.course,
+            geom_rate: fused_position
+                .climb_rate
+                .map(|climb_rate| climb_rate.round() as i64),
+            lat: fused_position.location.1,
+            lon: fused_position.location.0,
+            seen_pos: 0.0,
+            seen: 0.0,
+            messages: 0,
+            ts: fused_position.ts,
+        })
+    }
+}
+
+// keeps the current aircraft and writes them every second as "aircraft.json" in the readsb format (e.g. for tar1090)
+pub struct ReadsbActor {
+    pub output_dir: PathBuf,
+    pub position_stream: PositionStream,
+
+    pub aircraft: HashMap<String, ReadsbAircraft>,
+    pub messages: u64,
+    pub last_ts: Option<DateTime<Utc>>,
+}
+
+impl ReadsbActor {
+    pub fn new(output_dir: &str, position_stream: PositionStream) -> Self {
+        ReadsbActor {
+            output_dir: PathBuf::from(output_dir),
+            position_stream,
+            aircraft: HashMap::new(),
+            messages: 0,
+            last_ts: None,
+        }
+    }
+
+    // with both position streams only the raw positions are counted as messages, the fused positions are derived from them
+    fn update(&mut self, mut aircraft: ReadsbAircraft, is_message: bool) {
+        let count = u64::from(is_message);
+        self.messages += count;
+        self.last_ts = self.last_ts.max(Some(aircraft.ts));
+        match self.aircraft.get_mut(&aircraft.hex) {
+            Some(previous) if previous.ts > aircraft.ts => previous.messages += count,
+            Some(previous) => {
+                aircraft.messages = previous.messages + count;
+                // the flight level is not sent with every position
+                aircraft.alt_baro = aircraft.alt_baro.or(previous.alt_baro);
+                aircraft.r = aircraft.r.take().or(previous.r.take());
+                *previous = aircraft;
+            }
+            None => {
+                aircraft.messages = count;
+                self.aircraft.insert(aircraft.hex.clone(), aircraft);
+            }
+        }
+    }
+
+    // write to a temporary file first, so readers never see a partially written file
+    fn write(&self, file_name: &str, content: &impl Serialize) {
+        let path = self.output_dir.join(file_name);
+        let tmp_path = self.output_dir.join(format!(".{file_name}.tmp"));
+        match serde_json::to_string(content) {
+            Ok(json) => {
+                if let Err(err) =
+                    fs::write(&tmp_path, json).and_then(|_| fs::rename(&tmp_path, &path))
+                {
+                    error!("Error writing '{}': {err}", path.display());
+                }
+            }
+            Err(err) => error!("Error serializing '{file_name}': {err}"),
+        }
+    }
+
+    // "now" is the time of the latest position, so replayed logs are shown as they were received
+    fn write_aircraft(&mut self) {
+        let Some(now) = self.last_ts else {
+            return;
+        };
+        self.aircraft
+            .retain(|_, aircraft| (now - aircraft.ts).num_seconds() <= AIRCRAFT_LIFETIME);
+        for aircraft in self.aircraft.values_mut() {
+            let seen = (now - aircraft.ts).num_milliseconds() as f64 / 1000.0;
+            aircraft.seen = seen;
+            aircraft.seen_pos = seen;
+        }
+
+        let mut aircraft = self.aircraft.values().collect::<Vec<_>>();
+        aircraft.sort_by(|a, b| a.hex.cmp(&b.hex));
+        self.write(
+            "aircraft.json",
+            &ReadsbAircraftJson {
+                now: now.timestamp_millis() as f64 / 1000.0,
+                messages: self.messages,
+                aircraft,
+            },
+        );
+    }
+}
+
+impl Actor for ReadsbActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("ReadsbActor started");
+        if let Err(err) = fs::create_dir_all(&self.output_dir) {
+            error!(
+                "Error creating output directory '{}': {err}",
+                self.output_dir.display()
+            );
+        }
+
+        // tar1090 reads the refresh interval from receiver.json
+        self.write(
+            "receiver.json",
+            &serde_json::json!({
+                "version": format!("ogn-client {}", env!("CARGO_PKG_VERSION")),
+                "refresh": WRITE_INTERVAL * 1000,
+                "history": 0,
+            }),
+        );
+        ctx.run_interval(Duration::from_secs(WRITE_INTERVAL), |act, _ctx| {
+            act.write_aircraft();
+        });
+    }
+}
+
+impl Handler<ServerResponseContainer> for ReadsbActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: ServerResponseContainer, ctx: &mut Self::Context) {
+        <Self as Handler<Container>>::handle(self, msg.into(), ctx);
+    }
+}
+
+impl Handler<Container> for ReadsbActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: Container, _: &mut Self::Context) {
+        match &msg {
+            Container::Position(position) if position.protocol != Protocol::Receiver => {
+                if let Some(aircraft) = ReadsbAircraft::from_position(position) {
+                    self.update(aircraft, true);
+                }
+            }
+            Container::FusedPosition(fused_position)
+                if fused_position.protocol != Protocol::Receiver =>
+            {
+                if let Some(aircraft) = ReadsbAircraft::from_fused_position(fused_position) {
+                    self.update(aircraft, self.position_stream != PositionStream::Both);
+                }
+            }
+            _ => {
+                // Only aircraft positions are written
+            }
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use std::{
+    io::Write,
+    net::{Shutdown, TcpListener, TcpStream},
+    sync::{Arc, mpsc::Receiver},
+    thread,
+    time::Duration,
+};
+
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::*;
+
+use crate::{
+    containers::{
+        address_type::AddressType, containers::Container,
+        fused_position_container::FusedPositionContainer, position_container::PositionContainer,
+        protocol::Protocol,
+    },
+    messages::server_response_container::ServerResponseContainer,
+    utils::broadcast::Broadcast,
+};
+
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+// the fields of a position needed for SBS-1 (always aviation units: ft, kt, ft/min)
+struct SbsPosition {
+    ts: DateTime<Utc>,
+    hex: String,
+    altitude: Option<i64>,
+    speed: Option<u16>,
+    course: Option<u16>,
+    latitude: f64,
+    longitude: f64,
+    climb_rate: Option<f64>,
+}
+
+impl SbsPosition {
+    // SBS clients expect 6 hex digits, so non-ICAO addresses (which may collide with ICAO addresses) are optional
+    fn hex(
+        address: Option<u32>,
+        address_type_name: Option<AddressType>,
+        all_addresses: bool,
+    ) -> Option<String> {
+        if !all_addresses && address_type_name != Some(AddressType::Icao) {
+            return None;
+        }
+        address.map(|address| format!("{address:06X}"))
+    }
+
+    fn from_position(position: &PositionContainer, all_addresses: bool) -> Option<Self> {
+        Some(SbsPosition {
+            ts: position.ts,
+            hex: Self::hex(position.address, position.address_type_name, all_addresses)?,
+            // SBS altitude is barometric, the GPS altitude is taken if there is no flight level
+            altitude: position
+                .flight_level
+                .and_then(|flight_level| (flight_level * Decimal::ONE_HUNDRED).to_i64())
+                .or(position.altitude.map(i64::from)),
+            speed: position.speed,
+            course: position.course,
+            latitude: position.location.1,
+            longitude: position.location.0,
+            climb_rate: position.climb_rate,
+        })
+    }
+
+    fn from_fused_position(
+        fused_position: &FusedPositionContainer,
+        all_addresses: bool,
+    ) -> Option<Self> {
+        Some(SbsPosition {
+            ts: fused_position.ts,
+            hex: Self::hex(
+                fused_position.address,
+                fused_position.address_type_name,
+                all_addresses,
+            )?,
+            altitude: fused_position.altitude.map(i64::from),
+            speed: fused_position.speed,
+            course: fused_position.course,
+            latitude: fused_position.location.1,
+            longitude: fused_position.location.0,
+            climb_rate: fused_position.climb_rate,
+        })
+    }
+
+    // MSG,3 (airborne position) and, if there is any velocity, MSG,4 (airborne velocity)
+    fn to_sbs(&self) -> String {
+        let ts = self.ts.format("%Y/%m/%d,%H:%M:%S%.3f");
+        let header =
+            |transmission_type: u8| format!("MSG,{transmission_type},1,1,{},1,{ts},{ts}", self.hex);
+        let optional = |value: Option<String>| value.unwrap_or_default();
+
+        let mut lines = format!(
+            "{},,{},,,{:.5},{:.5},,,0,0,0,0\r\n",
+            header(3),
+            optional(self.altitude.map(|altitude| altitude.to_string())),
+            self.latitude,
+            self.longitude
+        );
+        if self.speed.is_some() || self.course.is_some() || self.climb_rate.is_some() {
+            lines.push_str(&format!(
+                "{},,,{},{},,,{},,0,0,0,0\r\n",
+                header(4),
+                optional(self.speed.map(|speed| speed.to_string())),
+                optional(self.course.map(|course| course.to_string())),
+                optional(
+                    self.climb_rate
+                        .map(|climb_rate| (climb_rate.round() as i64).to_string())
+                ),
+            ));
+        }
+        lines
+    }
+}
+
+// writes the queued messages to a client until the client is disconnected
+fn write_client(mut writer: TcpStream, receiver: Receiver<Arc<[u8]>>) {
+    for lines in receiver {
+        if let Err(err) = writer.write_all(&lines) {
+            info!("SBS client disconnected: {err}");
+            break;
+        }
+    }
+    let _ = writer.shutdown(Shutdown::Both);
+}
+
+fn accept(stream: TcpStream, clients: &Broadcast<Arc<[u8]>>) -> std::io::Result<()> {
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let writer = stream.try_clone()?;
+    clients.add(&stream, (), move |receiver| write_client(writer, receiver))
+}
+
+// serves the aircraft positions as SBS-1 BaseStation messages (CSV) to all connected TCP clients (e.g. Virtual Radar Server)
+pub struct SbsActor {
+    pub address: String,
+    pub all_addresses: bool,
+
+    clients: Arc<Broadcast<Arc<[u8]>>>,
+}
+
+impl SbsActor {
+    pub fn new(address: &str, all_addresses: bool) -> Self {
+        SbsActor {
+            address: address.to_string(),
+            all_addresses,
+            clients: Arc::new(Broadcast::new("SBS")),
+        }
+    }
+
+    fn send(&self, position: Option<SbsPosition>) {
+        let Some(position) = position else {
+            return;
+        };
+        if self.clients.is_empty() {
+            return;
+        }
+        let lines: Arc<[u8]> = position.to_sbs().into_bytes().into();
+        self.clients.send(lines, |_| true);
+    }
+}
+
+impl Actor for SbsActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _: &mut Self::Context) {
+        info!("SbsActor started");
+        match TcpListener::bind(&self.address) {
+            Ok(listener) => {
+                info!("SBS server listening on {}", self.address);
+                let clients = self.clients.clone();
+                thread::spawn(move || {
+                    for stream in listener.incoming() {
+                        if let Err(err) = stream.and_then(|stream| accept(stream, &clients)) {
+                            error!("Error accepting SBS connection: {err}");
+                        }
+                    }
+                });
+            }
+            Err(err) => error!("Error binding SBS server to '{}': {err}", self.address),
+        }
+    }
+}
+
+impl Handler<ServerResponseContainer> for SbsActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: ServerResponseContainer, ctx: &mut Self::Context) {
+        <Self as Handler<Container>>::handle(self, msg.into(), ctx);
+    }
+}
+
+impl Handler<Container> for SbsActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: Container, _: &mut Self::Context) {
+        match &msg {
+            Container::Position(position) if position.protocol != Protocol::Receiver => {
+                self.send(SbsPosition::from_position(position, self.all_addresses))
+            }
+            Container::FusedPosition(fused_position)
+                if fused_position.protocol != Protocol::Receiver =>
+            {
+                self.send(SbsPosition::from_fused_position(
+                    fused_position,
+                    self.all_addresses,
+                ))
+            }
+            _ => {
+                // Only aircraft positions are sent
+            }
+        }
+    }
+}
@@ -1,12 +1,17 @@
 use actix::prelude::*;
 
-use crate::messages::server_response_container::ServerResponseContainer;
+use crate::{
+    containers::{containers::Container, units::Units},
+    messages::server_response_container::ServerResponseContainer,
+};
 
-pub struct StdoutActor;
+pub struct StdoutActor {
+    pub units: Units,
+}
 
 impl StdoutActor {
-    pub fn new() -> Self {
-        StdoutActor
+    pub fn new(units: Units) -> Self {
+        StdoutActor { units }
     }
 }
 
@@ -29,3 +34,56 @@ impl Handler<ServerResponseContainer> for StdoutActor {
         }
     }
 }
+
+impl Handler<Container> for StdoutActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: Container, _: &mut Self::Context) {
+        // Derived containers have no raw message, so we print them as JSON
+        match msg.with_units(self.units) {
+            Container::FusedPosition(fused_position) => {
+                println!("{}", serde_json::to_string(&fused_position).unwrap());
+            }
+            Container::Flight(flight) => {
+                println!("{}", serde_json::to_string(&flight).unwrap());
+            }
+            Container::Thermal(thermal) => {
+                println!("{}", serde_json::to_string(&thermal).unwrap());
+            }
+            Container::ThermalMap(thermal_map) => {
+                println!("{}", serde_json::to_string(&thermal_map).unwrap());
+            }
+            Container::Wind(wind) => {
+                println!("{}", serde_json::to_string(&wind).unwrap());
+            }
+            Container::Qnh(qnh) => {
+                println!("{}", serde_json::to_string(&qnh).unwrap());
+            }
+            Container::Coverage(coverage) => {
+                println!("{}", serde_json::to_string(&coverage).unwrap());
+            }
+            Container::Polar(polar) => {
+                println!("{}", serde_json::to_string(&polar).unwrap());
+            }
+            Container::Alert(alert) => {
+                println!("{}", serde_json::to_string(&alert).unwrap());
+            }
+            Container::Timing(timing) => {
+                println!("{}", serde_json::to_string(&timing).unwrap());
+            }
+            Container::Sensitivity(sensitivity) => {
+                println!("{}", serde_json::to_string(&sensitivity).unwrap());
+            }
+            Container::ReceiverInventory(inventory) => {
+                println!("{}", serde_json::to_string(&inventory).unwrap());
+            }
+            Container::ReceiverVersion(version) => {
+                println!("{}", serde_json::to_string(&version).unwrap());
+            }
+            Container::DeviceInventory(inventory) => {
+                println!("{}", serde_json::to_string(&inventory).unwrap());
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    io::{self, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    sync::mpsc::{self, Receiver, SyncSender, TrySendError},
+    thread,
+    time::Duration,
+};
+
+use actix::prelude::*;
+
+use crate::{
+    containers::containers::Container, messages::server_response_container::ServerResponseContainer,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+const QUEUE_SIZE: usize = 100; // alerts waiting to be posted, more are dropped
+
+// HTTP endpoint (plain http only)
+struct Webhook {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl Webhook {
+    // url format: http://<HOST>[:<PORT>][/<PATH>]
+    fn new(url: &str) -> Self {
+        let address = url.strip_prefix("http://").unwrap_or_else(|| {
+            error!("Webhook url '{url}' is not a plain http url");
+            url
+        });
+        let (authority, path) = match address.find('/') {
+            Some(index) => address.split_at(index),
+            None => (address, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse().unwrap_or_else(|err| {
+                    error!("Invalid webhook port '{port}': {err}");
+                    80
+                }),
+            ),
+            None => (authority, 80),
+        };
+
+        Webhook {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        }
+    }
+
+    fn connect(&self) -> io::Result<TcpStream> {
+        let mut last_err = io::Error::new(io::ErrorKind::NotFound, "host not found");
+        for address in (self.host.as_str(), self.port).to_socket_addrs()? {
+            match TcpStream::connect_timeout(&address, TIMEOUT) {
+                Ok(stream) => return Ok(stream),
+                Err(err) => last_err = err,
+            }
+        }
+        Err(last_err)
+    }
+
+    fn post(&self, body: &str) -> io::Result<String> {
+        let mut stream = self.connect()?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            self.path,
+            self.host,
+            body.len()
+        );
+        stream.write_all(request.as_bytes())?;
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+        Ok(response.lines().next().unwrap_or_default().to_string())
+    }
+
+    // post the alerts one after the other, until the actor is dropped
+    fn run(&self, receiver: Receiver<String>) {
+        for body in receiver {
+            match self.post(&body) {
+                Ok(status) => trace!("Webhook response: {status}"),
+                Err(err) => error!("Error posting alert to webhook: {err}"),
+            }
+        }
+    }
+}
+
+// posts alerts as JSON to a webhook, the requests are made by a worker thread so a slow endpoint doesn't block the arbiter
+pub struct WebhookActor {
+    sender: SyncSender<String>,
+}
+
+impl WebhookActor {
+    pub fn new(url: &str) -> Self {
+        let webhook = Webhook::new(url);
+        let (sender, receiver) = mpsc::sync_channel(QUEUE_SIZE);
+        thread::spawn(move || webhook.run(receiver));
+
+        WebhookActor { sender }
+    }
+}
+
+impl Actor for WebhookActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _: &mut Self::Context) {
+        info!("WebhookActor started");
+    }
+}
+
+impl Handler<ServerResponseContainer> for WebhookActor {
+    type Result = ();
+
+    fn handle(&mut self, _: ServerResponseContainer, _: &mut Self::Context) {
+        // Single messages are not posted, only alerts
+    }
+}
+
+impl Handler<Container> for WebhookActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: Container, _: &mut Self::Context) {
+        if let Container::Alert(alert) = msg {
+            match self.sender.try_send(serde_json::to_string(&alert).unwrap()) {
+                Ok(_) => (),
+                Err(TrySendError::Full(_)) => error!("Webhook queue is full, alert dropped"),
+                Err(TrySendError::Disconnected(_)) => error!("Webhook worker has stopped"),
+            }
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use std::{
+    io::ErrorKind,
+    net::{Shutdown, TcpListener, TcpStream},
+    sync::{
+        Arc, Mutex,
+        mpsc::{Receiver, RecvTimeoutError},
+    },
+    thread,
+    time::Duration,
+};
+
+use actix::prelude::*;
+use serde::{Deserialize, Serialize};
+use tungstenite::{
+    Message, Utf8Bytes, WebSocket,
+    handshake::server::{create_response, write_response},
+    protocol::{Role, WebSocketConfig},
+};
+
+use crate::{
+    containers::{containers::Container, protocol::Protocol, units::Units},
+    messages::server_response_container::ServerResponseContainer,
+    utils::{broadcast::Broadcast, http},
+};
+
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+const POLL_INTERVAL: Duration = Duration::from_millis(100); // the client is read at least this often
+const MAX_MESSAGE_SIZE: usize = 64 * 1024; // [bytes] a subscription is much smaller
+
+// filter of a client, all given criteria must match, an empty subscription gets everything
+#[derive(Debug, Default, Deserialize)]
+pub struct Subscription {
+    pub bbox: Option<(f64, f64, f64, f64)>,
+    pub protocols: Option<Vec<String>>,
+    pub addresses: Option<Vec<String>>,
+}
+
+impl Subscription {
+    // query format: ?bbox=<WEST>,<SOUTH>,<EAST>,<NORTH>&protocols=flarm,fanet&addresses=DD1234,ICA3D1C35
+    fn from_request(request: &http::Request) -> Result<Self, String> {
+        let query = http::query(request);
+        let list = |key: &str| {
+            query
+                .get(key)
+                .map(|value| value.split(',').map(str::to_string).collect())
+        };
+        Ok(Subscription {
+            bbox: query
+                .get("bbox")
+                .map(|bbox| http::parse_bbox(bbox))
+                .transpose()?,
+            protocols: list("protocols"),
+            addresses: list("addresses"),
+        })
+    }
+
+    // statuses have no location, they are not filtered by the bounding box
+    fn matches(
+        &self,
+        src_call: &str,
+        protocol: Protocol,
+        address: Option<u32>,
+        location: Option<(f64, f64)>,
+    ) -> bool {
+        let in_bbox = match (self.bbox, location) {
+            (Some(bbox), Some((longitude, latitude))) => {
+                http::is_in_bbox(bbox, latitude, longitude)
+            }
+            _ => true,
+        };
+        let has_protocol = self.protocols.as_ref().is_none_or(|protocols| {
+            protocols
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(protocol.as_str()))
+        });
+        let has_address = self.addresses.as_ref().is_none_or(|addresses| {
+            addresses.iter().any(|value| {
+                value.eq_ignore_ascii_case(src_call)
+                    || address.is_some_and(|address| {
+                        value.eq_ignore_ascii_case(&format!("{address:06X}"))
+                    })
+            })
+        });
+        in_bbox && has_protocol && has_address
+    }
+}
+
+#[derive(Serialize)]
+struct Event<'a, T: Serialize> {
+    #[serde(rename = "type")]
+    event_type: &'a str,
+    data: &'a T,
+}
+
+type Clients = Broadcast<Utf8Bytes, Arc<Mutex<Subscription>>>;
+
+// reads a message of the client without waiting for it
+fn try_read(socket: &mut WebSocket<TcpStream>) -> tungstenite::Result<Option<Message>> {
+    socket.get_ref().set_nonblocking(true)?;
+    let message = socket.read();
+    socket.get_ref().set_nonblocking(false)?;
+    match message {
+        Ok(message) => Ok(Some(message)),
+        Err(tungstenite::Error::Io(err)) if err.kind() == ErrorKind::WouldBlock => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+// writes the queued messages to a client and reads its messages until the client is disconnected:
+// a text message replaces the subscription (JSON), pings and close frames are answered by tungstenite
+fn serve_client(
+    socket: &mut WebSocket<TcpStream>,
+    receiver: &Receiver<Utf8Bytes>,
+    subscription: &Mutex<Subscription>,
+) -> tungstenite::Result<()> {
+    loop {
+        match receiver.recv_timeout(POLL_INTERVAL) {
+            Ok(text) => {
+                socket.write(Message::Text(text))?;
+                for text in receiver.try_iter() {
+                    socket.write(Message::Text(text))?;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            // the actor is gone
+            Err(RecvTimeoutError::Disconnected) => return socket.close(None),
+        }
+        socket.flush()?;
+
+        while let Some(message) = try_read(socket)? {
+            match message {
+                Message::Text(text) => match serde_json::from_str::<Subscription>(&text) {
+                    Ok(new_subscription) => *subscription.lock().unwrap() = new_subscription,
+                    Err(err) => error!("Invalid WebSocket subscription: {err}"),
+                },
+                // the reply to the close frame is queued, it is sent with the flush
+                Message::Close(_) => return socket.flush(),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn run_client(
+    mut socket: WebSocket<TcpStream>,
+    receiver: Receiver<Utf8Bytes>,
+    subscription: Arc<Mutex<Subscription>>,
+) {
+    match serve_client(&mut socket, &receiver, &subscription) {
+        Ok(_) | Err(tungstenite::Error::ConnectionClosed) => {}
+        Err(err) => info!("WebSocket client disconnected: {err}"),
+    }
+    // the client is removed with the next message
+    let _ = socket.get_ref().shutdown(Shutdown::Both);
+}
+
+fn accept(mut stream: TcpStream, clients: &Clients) -> Result<(), String> {
+    let request = http::read_request(&stream).ok_or("invalid request")?;
+    let subscription = match Subscription::from_request(&request) {
+        Ok(subscription) => subscription,
+        Err(err) => {
+            let _ = http::write_response(&mut stream, "400 Bad Request", "text/plain", &err);
+            return Err(err);
+        }
+    };
+    let response = create_response(&request).map_err(|err| err.to_string())?;
+
+    stream
+        .set_write_timeout(Some(WRITE_TIMEOUT))
+        .map_err(|err| err.to_string())?;
+    write_response(&mut stream, &response).map_err(|err| err.to_string())?;
+
+    let config = WebSocketConfig::default()
+        .max_message_size(Some(MAX_MESSAGE_SIZE))
+        .max_frame_size(Some(MAX_MESSAGE_SIZE));
+    let socket = WebSocket::from_raw_socket(
+        stream.try_clone().map_err(|err| err.to_string())?,
+        Role::Server,
+        Some(config),
+    );
+    let subscription = Arc::new(Mutex::new(subscription));
+    clients
+        .add(&stream, subscription.clone(), move |receiver| {
+            run_client(socket, receiver, subscription)
+        })
+        .map_err(|err| err.to_string())
+}
+
+// streams positions and statuses as JSON ({"type": ..., "data": ...}) to WebSocket clients
+pub struct WebSocketActor {
+    pub address: String,
+    pub units: Units,
+
+    clients: Arc<Clients>,
+}
+
+impl WebSocketActor {
+    pub fn new(address: &str, units: Units) -> Self {
+        WebSocketActor {
+            address: address.to_string(),
+            units,
+            clients: Arc::new(Broadcast::new("WebSocket")),
+        }
+    }
+
+    fn send<T: Serialize>(
+        &self,
+        event_type: &str,
+        data: &T,
+        src_call: &str,
+        protocol: Protocol,
+        address: Option<u32>,
+        location: Option<(f64, f64)>,
+    ) {
+        if self.clients.is_empty() {
+            return;
+        }
+        let text = Utf8Bytes::from(serde_json::to_string(&Event { event_type, data }).unwrap());
+        self.clients.send(text, |subscription| {
+            subscription
+                .lock()
+                .unwrap()
+                .matches(src_call, protocol, address, location)
+        });
+    }
+}
+
+impl Actor for WebSocketActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _: &mut Self::Context) {
+        info!("WebSocketActor started");
+        match TcpListener::bind(&self.address) {
+            Ok(listener) => {
+                info!("WebSocket server listening on ws://{}", self.address);
+                let clients = self.clients.clone();
+                thread::spawn(move || {
+                    for stream in listener.incoming() {
+                        match stream {
+                            // the handshake is done on a separate thread
+                            Ok(stream) => {
+                                let clients = clients.clone();
+                                thread::spawn(move || {
+                                    if let Err(err) = accept(stream, &clients) {
+                                        error!("Error accepting WebSocket client: {err}");
+                                    }
+                                });
+                            }
+                            Err(err) => error!("Error accepting WebSocket connection: {err}"),
+                        }
+                    }
+                });
+            }
+            Err(err) => error!(
+                "Error binding WebSocket server to '{}': {err}",
+                self.address
+            ),
+        }
+    }
+}
+
+impl Handler<ServerResponseContainer> for WebSocketActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: ServerResponseContainer, ctx: &mut Self::Context) {
+        <Self as Handler<Container>>::handle(self, msg.into(), ctx);
+    }
+}
+
+impl Handler<Container> for WebSocketActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: Container, _: &mut Self::Context) {
+        match msg.with_units(self.units) {
+            Container::Position(position) => self.send(
+                "position",
+                &position,
+                &position.src_call.to_string(),
+                position.protocol,
+                position.address,
+                Some(position.location),
+            ),
+            Container::FusedPosition(fused_position) => self.send(
+                "fused_position",
+                &fused_position,
+                &fused_position.src_call.to_string(),
+                fused_position.protocol,
+                fused_position.address,
+                Some(fused_position.location),
+            ),
+            Container::Status(status) => self.send(
+                "status",
+                &status,
+                &status.src_call.to_string(),
+                status.protocol,
+                None,
+                None,
+            ),
+            _ => {
+                // Only positions and statuses are streamed
+            }
+        }
+    }
+}
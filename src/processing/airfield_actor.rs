@@ -0,0 +1,80 @@
+use actix::prelude::*;
+use ogn_parser::{AprsData, ServerResponse};
+
+use crate::{
+    airfields::airfield_database::AirfieldDatabase, containers::units::FOOT,
+    messages::server_response_container::ServerResponseContainer,
+};
+
+// only positions up to this height above the airfield are annotated [m]
+const NEAR_GROUND_HEIGHT: f64 = 300.0;
+
+pub struct AirfieldActor {
+    pub recipient: Recipient<ServerResponseContainer>,
+
+    pub airfield_database: AirfieldDatabase,
+    pub max_distance: f64,
+}
+
+impl AirfieldActor {
+    pub fn new(
+        recipient: Recipient<ServerResponseContainer>,
+        airfield_database: AirfieldDatabase,
+        max_distance: f64,
+    ) -> Self {
+        AirfieldActor {
+            recipient,
+
+            airfield_database,
+            max_distance,
+        }
+    }
+}
+
+impl Actor for AirfieldActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _: &mut Self::Context) {
+        info!(
+            "AirfieldActor started with {} airfields",
+            self.airfield_database.airfields.len()
+        );
+    }
+}
+
+impl Handler<ServerResponseContainer> for AirfieldActor {
+    type Result = ();
+
+    fn handle(&mut self, mut msg: ServerResponseContainer, _: &mut Self::Context) {
+        if let ServerResponse::AprsPacket(packet) = &msg.server_response
+            && let AprsData::Position(position) = &packet.data
+            && position.comment.id.is_some()
+            && let Some((airfield, distance)) = self.airfield_database.nearest(
+                *position.latitude,
+                *position.longitude,
+                self.max_distance,
+            )
+        {
+            let is_near_ground = match (position.comment.altitude, airfield.elevation) {
+                (Some(altitude), Some(elevation)) => {
+                    altitude as f64 * FOOT - elevation < NEAR_GROUND_HEIGHT
+                }
+                _ => true,
+            };
+
+            if is_near_ground {
+                msg.airfield = Some(airfield.code.clone());
+                msg.airfield_distance = Some(distance);
+                msg.airfield_elevation = airfield.elevation;
+            }
+        }
+
+        // Forward the message to the next actor in the chain
+        match self.recipient.do_send(msg) {
+            Ok(_) => (),
+            Err(err) => {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use ogn_parser::{AprsData, Callsign, ServerResponse};
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::{
+    messages::{circling_event::CirclingEvent, server_response_container::ServerResponseContainer},
+    utils::circling::{CirclingDetector, CirclingFix},
+};
+
+const TIMEOUT: i64 = 30 * 60; // [s] aircraft not seen for this time is removed
+
+pub struct Aircraft {
+    pub src_call: Callsign,
+    pub last_ts: Option<DateTime<Utc>>,
+    pub detector: CirclingDetector,
+}
+
+// detects the circling of all aircraft once and sends circles and ended circling phases to the subscribers
+pub struct CirclingActor {
+    pub recipient: Recipient<ServerResponseContainer>,
+    pub subscribers: Vec<Recipient<CirclingEvent>>,
+
+    pub aircrafts: HashMap<String, Aircraft>,
+    pub last_ts: Option<DateTime<Utc>>,
+}
+
+impl CirclingActor {
+    pub fn new(
+        recipient: Recipient<ServerResponseContainer>,
+        subscribers: Vec<Recipient<CirclingEvent>>,
+    ) -> Self {
+        CirclingActor {
+            recipient,
+            subscribers,
+
+            aircrafts: HashMap::new(),
+            last_ts: None,
+        }
+    }
+
+    fn send(&self, event: CirclingEvent) {
+        for subscriber in &self.subscribers {
+            if let Err(err) = subscriber.do_send(event.clone()) {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+
+    fn update(&mut self, src_call: &Callsign, fix: CirclingFix, turn_rate: Option<f64>) {
+        let aircraft = self
+            .aircrafts
+            .entry(src_call.to_string())
+            .or_insert_with(|| Aircraft {
+                src_call: src_call.clone(),
+                last_ts: None,
+                detector: CirclingDetector::default(),
+            });
+
+        // copies of the same position from other receivers
+        if aircraft.last_ts.is_some_and(|last_ts| fix.ts <= last_ts) {
+            return;
+        }
+        aircraft.last_ts = Some(fix.ts);
+
+        let course = fix.course;
+        let update = aircraft.detector.update(fix, course, turn_rate);
+        if let Some(circling) = update.ended {
+            self.send(CirclingEvent::Ended {
+                src_call: src_call.clone(),
+                circling: Arc::new(circling),
+            });
+        }
+        if let Some(circle) = update.circle {
+            self.send(CirclingEvent::Circle {
+                src_call: src_call.clone(),
+                circle,
+            });
+        }
+    }
+
+    // remove aircrafts we didn't see for a long time, their circling phase ends here
+    fn remove_outdated(&mut self) {
+        let Some(last_ts) = self.last_ts else {
+            return;
+        };
+
+        let mut ended = vec![];
+        self.aircrafts.retain(|_, aircraft| {
+            let is_outdated = aircraft.last_ts.is_none_or(|aircraft_ts| {
+                last_ts.signed_duration_since(aircraft_ts).num_seconds() > TIMEOUT
+            });
+            if is_outdated && let Some(circling) = aircraft.detector.finish() {
+                ended.push(CirclingEvent::Ended {
+                    src_call: aircraft.src_call.clone(),
+                    circling: Arc::new(circling),
+                });
+            }
+            !is_outdated
+        });
+        for event in ended {
+            self.send(event);
+        }
+    }
+}
+
+impl Actor for CirclingActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("CirclingActor started");
+        ctx.run_interval(Duration::from_secs(60), |act, _ctx| {
+            act.remove_outdated();
+        });
+    }
+}
+
+impl Handler<ServerResponseContainer> for CirclingActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: ServerResponseContainer, _: &mut Self::Context) {
+        if let ServerResponse::AprsPacket(packet) = &msg.server_response
+            && let AprsData::Position(position) = &packet.data
+            && position.comment.id.is_some()
+            && let Some(altitude) = position.comment.altitude
+        {
+            let ts = msg.receiver_ts.unwrap_or(msg.ts);
+            self.last_ts = self.last_ts.max(Some(ts));
+
+            let fix = CirclingFix {
+                ts,
+                latitude: *position.latitude,
+                longitude: *position.longitude,
+                altitude,
+                course: position.comment.course,
+            };
+            let turn_rate = position
+                .comment
+                .turn_rate
+                .and_then(|turn_rate| turn_rate.to_f64());
+            self.update(&packet.from, fix, turn_rate);
+        }
+
+        // Forward the message to the next actor in the chain
+        match self.recipient.do_send(msg) {
+            Ok(_) => (),
+            Err(err) => {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
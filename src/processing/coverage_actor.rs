@@ -0,0 +1,210 @@
+use std::{collections::HashMap, time::Duration};
+
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use ogn_parser::{AprsData, ServerResponse};
+
+use crate::{
+    containers::{
+        containers::Container,
+        coverage_container::{CoverageCell, CoverageContainer},
+        units::Units,
+    },
+    messages::server_response_container::ServerResponseContainer,
+};
+
+const COVERAGE_INTERVAL: u64 = 10 * 60; // [s] the coverage is sent periodically
+const COVERAGE_LIFETIME: i64 = 24 * 60 * 60; // [s] cells without positions for this time are removed
+
+type Cell = (i32, i32);
+
+#[derive(Debug, Default)]
+pub struct CellStatistics {
+    pub message_count: u32,
+    pub quality_count: u32,
+    pub quality_sum: f64,
+    pub max_normalized_quality: Option<f64>,
+    pub min_altitude: Option<u32>,
+    pub max_distance: Option<f64>,
+    pub last_ts: Option<DateTime<Utc>>,
+
+    // positions since the last snapshot
+    pub new_message_count: u32,
+    pub new_quality_count: u32,
+    pub new_quality_sum: f64,
+}
+
+pub struct CoverageActor {
+    pub recipient: Recipient<ServerResponseContainer>,
+    pub coverage_recipient: Recipient<Container>,
+    pub resolution: f64,
+
+    pub receivers: HashMap<String, HashMap<Cell, CellStatistics>>,
+    pub last_ts: Option<DateTime<Utc>>,
+}
+
+impl CoverageActor {
+    pub fn new(
+        recipient: Recipient<ServerResponseContainer>,
+        coverage_recipient: Recipient<Container>,
+        resolution: f64,
+    ) -> Self {
+        CoverageActor {
+            recipient,
+            coverage_recipient,
+            resolution,
+
+            receivers: HashMap::new(),
+            last_ts: None,
+        }
+    }
+
+    fn update(&mut self, msg: &ServerResponseContainer) {
+        let ServerResponse::AprsPacket(packet) = &msg.server_response else {
+            return;
+        };
+        let AprsData::Position(position) = &packet.data else {
+            return;
+        };
+        // only aircraft received by a known receiver
+        let (Some(receiver), Some(distance)) = (packet.via.last(), msg.distance) else {
+            return;
+        };
+        if position.comment.id.is_none() {
+            return;
+        }
+
+        let ts = msg.receiver_ts.unwrap_or(msg.ts);
+        self.last_ts = self.last_ts.max(Some(ts));
+
+        let cell = (
+            (*position.latitude / self.resolution).floor() as i32,
+            (*position.longitude / self.resolution).floor() as i32,
+        );
+        let statistics = self
+            .receivers
+            .entry(receiver.to_string())
+            .or_default()
+            .entry(cell)
+            .or_default();
+
+        statistics.message_count += 1;
+        statistics.new_message_count += 1;
+        if let Some(quality) = msg.normalized_signal_quality {
+            statistics.quality_count += 1;
+            statistics.quality_sum += quality;
+            statistics.new_quality_count += 1;
+            statistics.new_quality_sum += quality;
+            statistics.max_normalized_quality = Some(
+                statistics
+                    .max_normalized_quality
+                    .map_or(quality, |q| q.max(quality)),
+            );
+        }
+        if let Some(altitude) = position.comment.altitude {
+            statistics.min_altitude = Some(
+                statistics
+                    .min_altitude
+                    .map_or(altitude, |a| a.min(altitude)),
+            );
+        }
+        statistics.max_distance = Some(
+            statistics
+                .max_distance
+                .map_or(distance, |d| d.max(distance)),
+        );
+        statistics.last_ts = statistics.last_ts.max(Some(ts));
+    }
+
+    // remove old cells and send the coverage of all receivers
+    fn send_coverage(&mut self) {
+        let Some(last_ts) = self.last_ts else {
+            return;
+        };
+
+        for cells in self.receivers.values_mut() {
+            cells.retain(|_, statistics| {
+                statistics.last_ts.is_some_and(|ts| {
+                    last_ts.signed_duration_since(ts).num_seconds() <= COVERAGE_LIFETIME
+                })
+            });
+        }
+        self.receivers.retain(|_, cells| !cells.is_empty());
+
+        let cells = self
+            .receivers
+            .iter()
+            .flat_map(|(receiver, cells)| {
+                cells
+                    .iter()
+                    .map(|((latitude, longitude), statistics)| CoverageCell {
+                        ts: last_ts,
+                        units: Units::Aviation,
+                        receiver: receiver.clone(),
+                        location: (
+                            (*longitude as f64 + 0.5) * self.resolution,
+                            (*latitude as f64 + 0.5) * self.resolution,
+                        ),
+                        resolution: self.resolution,
+                        message_count: statistics.message_count,
+                        max_normalized_quality: statistics.max_normalized_quality,
+                        mean_normalized_quality: (statistics.quality_count > 0)
+                            .then(|| statistics.quality_sum / statistics.quality_count as f64),
+                        min_altitude: statistics.min_altitude,
+                        max_distance: statistics.max_distance,
+                        new_message_count: statistics.new_message_count,
+                        new_quality_count: statistics.new_quality_count,
+                        new_quality_sum: statistics.new_quality_sum,
+                    })
+            })
+            .collect::<Vec<_>>();
+        for statistics in self
+            .receivers
+            .values_mut()
+            .flat_map(|cells| cells.values_mut())
+        {
+            statistics.new_message_count = 0;
+            statistics.new_quality_count = 0;
+            statistics.new_quality_sum = 0.0;
+        }
+
+        let container = CoverageContainer {
+            ts: last_ts,
+            units: Units::Aviation,
+            cells,
+        };
+        if let Err(err) = self
+            .coverage_recipient
+            .do_send(Container::Coverage(container))
+        {
+            error!("Error sending message to recipient: {err}");
+        }
+    }
+}
+
+impl Actor for CoverageActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("CoverageActor started");
+        ctx.run_interval(Duration::from_secs(COVERAGE_INTERVAL), |act, _ctx| {
+            act.send_coverage();
+        });
+    }
+}
+
+impl Handler<ServerResponseContainer> for CoverageActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: ServerResponseContainer, _: &mut Self::Context) {
+        self.update(&msg);
+
+        // Forward the message to the next actor in the chain
+        match self.recipient.do_send(msg) {
+            Ok(_) => (),
+            Err(err) => {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
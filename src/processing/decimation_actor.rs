@@ -0,0 +1,163 @@
+use std::{collections::HashMap, str::FromStr, time::Duration};
+
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use ogn_parser::{AprsData, AprsPosition, ServerResponse};
+
+use crate::messages::server_response_container::ServerResponseContainer;
+
+const CLEANUP_INTERVAL: u64 = 60; // [s]
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecimationConfig {
+    // minimum time between two positions of the same sender [s]
+    pub interval: u64,
+
+    // let pass earlier if the sender moved more than this distance [m]
+    pub distance: Option<f64>,
+
+    // let pass earlier if the course changed more than this angle [°]
+    pub heading: Option<u16>,
+}
+
+// format: <SECONDS>[,<METRES>[,<DEGREES>]], e.g. "10", "10,500" or "10,,20"
+impl FromStr for DecimationConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(',');
+
+        let interval = parts
+            .next()
+            .unwrap_or_default()
+            .parse::<u64>()
+            .map_err(|err| format!("invalid interval '{s}': {err}"))?;
+        let distance = match parts.next() {
+            Some("") | None => None,
+            Some(part) => match part.parse::<f64>() {
+                Ok(distance) if distance >= 0.0 => Some(distance),
+                Ok(_) => return Err(format!("distance must not be negative, got '{part}'")),
+                Err(err) => return Err(format!("invalid distance '{part}': {err}")),
+            },
+        };
+        let heading = match parts.next() {
+            Some("") | None => None,
+            Some(part) => Some(
+                part.parse::<u16>()
+                    .map_err(|err| format!("invalid heading '{part}': {err}"))?,
+            ),
+        };
+
+        if parts.next().is_some() {
+            return Err(format!("too many values in '{s}'"));
+        }
+
+        Ok(DecimationConfig {
+            interval,
+            distance,
+            heading,
+        })
+    }
+}
+
+pub struct DecimationActor {
+    pub recipient: Recipient<ServerResponseContainer>,
+
+    pub config: DecimationConfig,
+    pub last_positions: HashMap<String, (DateTime<Utc>, AprsPosition)>,
+    pub last_ts: Option<DateTime<Utc>>,
+}
+
+impl DecimationActor {
+    pub fn new(recipient: Recipient<ServerResponseContainer>, config: DecimationConfig) -> Self {
+        DecimationActor {
+            recipient,
+
+            config,
+            last_positions: HashMap::new(),
+            last_ts: None,
+        }
+    }
+
+    // a sender whose last position is older than the interval passes anyway, so it can be forgotten
+    fn cleanup(&mut self) {
+        if let Some(last_ts) = self.last_ts {
+            let interval = self.config.interval as i64;
+            self.last_positions
+                .retain(|_, (ts, _)| last_ts.signed_duration_since(*ts).num_seconds() < interval);
+        }
+    }
+
+    fn is_relevant(
+        &self,
+        ts: &DateTime<Utc>,
+        previous_ts: &DateTime<Utc>,
+        position: &AprsPosition,
+        previous_position: &AprsPosition,
+    ) -> bool {
+        if ts.signed_duration_since(*previous_ts).num_seconds() >= self.config.interval as i64 {
+            return true;
+        }
+
+        if let Some(max_distance) = self.config.distance
+            && position.get_relation(previous_position).distance > max_distance
+        {
+            return true;
+        }
+
+        if let (Some(max_heading), Some(course), Some(previous_course)) = (
+            self.config.heading,
+            position.comment.course,
+            previous_position.comment.course,
+        ) {
+            let delta = (course as i32 - previous_course as i32).rem_euclid(360);
+            if delta.min(360 - delta) > max_heading as i32 {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+impl Actor for DecimationActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("DecimationActor started");
+        ctx.run_interval(Duration::from_secs(CLEANUP_INTERVAL), |act, _ctx| {
+            act.cleanup();
+        });
+    }
+}
+
+impl Handler<ServerResponseContainer> for DecimationActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: ServerResponseContainer, _: &mut Self::Context) {
+        if let ServerResponse::AprsPacket(packet) = &msg.server_response
+            && let AprsData::Position(position) = &packet.data
+        {
+            let ts = msg.receiver_ts.unwrap_or(msg.ts);
+            let sender_name = &packet.from.call;
+            self.last_ts = self.last_ts.max(Some(ts));
+
+            if let Some((previous_ts, previous_position)) = self.last_positions.get(sender_name)
+                && !self.is_relevant(&ts, previous_ts, position, previous_position)
+            {
+                return;
+            }
+
+            self.last_positions
+                .insert(sender_name.to_string(), (ts, position.clone()));
+        }
+
+        // Forward the message to the next actor in the chain
+        match self.recipient.do_send(msg) {
+            Ok(_) => (),
+            Err(err) => {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
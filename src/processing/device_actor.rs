@@ -0,0 +1,214 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    time::Duration,
+};
+
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use ogn_parser::{AprsData, ServerResponse};
+use rust_decimal::{Decimal, prelude::ToPrimitive};
+
+use crate::{
+    containers::{
+        address_type::AddressType,
+        aircraft_type::AircraftType,
+        containers::Container,
+        device_container::{DeviceInventory, DeviceInventoryContainer},
+    },
+    messages::server_response_container::ServerResponseContainer,
+};
+
+const INVENTORY_INTERVAL: u64 = 5 * 60; // [s] the changed devices are sent periodically
+const DEVICE_LIFETIME: i64 = 60 * 60; // [s] devices not seen for this time are removed (after they were sent)
+
+type DeviceKey = (u16, u32);
+
+pub struct Device {
+    pub aircraft_type: u8,
+    pub src_call: String,
+    pub software_version: Option<Decimal>,
+    pub hardware_version: Option<u8>,
+    pub original_address: Option<u32>,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+
+    // statistics since the last inventory
+    pub message_count: u32,
+    pub frequency_offset_count: u32,
+    pub frequency_offset_sum: f64,
+    pub receivers: BTreeSet<String>,
+    pub changed: bool,
+}
+
+pub struct DeviceActor {
+    pub recipient: Recipient<ServerResponseContainer>,
+    pub inventory_recipient: Recipient<Container>,
+
+    pub devices: HashMap<DeviceKey, Device>,
+    pub last_ts: Option<DateTime<Utc>>,
+}
+
+impl DeviceActor {
+    pub fn new(
+        recipient: Recipient<ServerResponseContainer>,
+        inventory_recipient: Recipient<Container>,
+    ) -> Self {
+        DeviceActor {
+            recipient,
+            inventory_recipient,
+
+            devices: HashMap::new(),
+            last_ts: None,
+        }
+    }
+
+    fn update(&mut self, msg: &ServerResponseContainer) {
+        let ServerResponse::AprsPacket(packet) = &msg.server_response else {
+            return;
+        };
+        let AprsData::Position(position) = &packet.data else {
+            return;
+        };
+        let Some(id) = &position.comment.id else {
+            return;
+        };
+
+        let ts = msg.receiver_ts.unwrap_or(msg.ts);
+        self.last_ts = self.last_ts.max(Some(ts));
+        let device = self
+            .devices
+            .entry((id.address_type, id.address))
+            .or_insert_with(|| Device {
+                aircraft_type: id.aircraft_type,
+                src_call: packet.from.to_string(),
+                software_version: None,
+                hardware_version: None,
+                original_address: None,
+                first_seen: ts,
+                last_seen: ts,
+                message_count: 0,
+                frequency_offset_count: 0,
+                frequency_offset_sum: 0.0,
+                receivers: BTreeSet::new(),
+                changed: true,
+            });
+
+        // the latest values win, missing values don't overwrite known ones
+        if ts >= device.last_seen {
+            device.aircraft_type = id.aircraft_type;
+            device.src_call = packet.from.to_string();
+            device.software_version = position
+                .comment
+                .software_version
+                .or(device.software_version);
+            device.hardware_version = position
+                .comment
+                .hardware_version
+                .or(device.hardware_version);
+            device.original_address = position
+                .comment
+                .original_address
+                .or(device.original_address);
+        }
+        device.first_seen = device.first_seen.min(ts);
+        device.last_seen = device.last_seen.max(ts);
+        device.message_count += 1;
+        if let Some(frequency_offset) = position.comment.frequency_offset.and_then(|f| f.to_f64()) {
+            device.frequency_offset_count += 1;
+            device.frequency_offset_sum += frequency_offset;
+        }
+        if let Some(receiver) = packet.via.last() {
+            device.receivers.insert(receiver.to_string());
+        }
+        device.changed = true;
+    }
+
+    // send the changed devices with their statistics since the last inventory, remove devices not seen for a long time
+    fn send_inventory(&mut self) {
+        let Some(last_ts) = self.last_ts else {
+            return;
+        };
+
+        let devices = self
+            .devices
+            .iter_mut()
+            .filter(|(_, device)| device.changed)
+            .map(|((address_type, address), device)| {
+                let inventory = DeviceInventory {
+                    address: *address,
+                    address_type: *address_type,
+                    address_type_name: AddressType::new(*address_type),
+                    aircraft_type: device.aircraft_type,
+                    aircraft_type_name: AircraftType::from(device.aircraft_type),
+                    src_call: device.src_call.clone(),
+                    software_version: device.software_version,
+                    hardware_version: device.hardware_version,
+                    original_address: device.original_address,
+                    first_seen: device.first_seen,
+                    last_seen: device.last_seen,
+                    message_count: device.message_count,
+                    frequency_offset_count: device.frequency_offset_count,
+                    mean_frequency_offset: (device.frequency_offset_count > 0).then(|| {
+                        device.frequency_offset_sum / device.frequency_offset_count as f64
+                    }),
+                    receiver_count: device.receivers.len(),
+                    receivers: serde_json::to_string(&device.receivers).unwrap(),
+                };
+
+                device.changed = false;
+                device.message_count = 0;
+                device.frequency_offset_count = 0;
+                device.frequency_offset_sum = 0.0;
+                device.receivers.clear();
+                inventory
+            })
+            .collect::<Vec<_>>();
+        self.devices.retain(|_, device| {
+            last_ts
+                .signed_duration_since(device.last_seen)
+                .num_seconds()
+                <= DEVICE_LIFETIME
+        });
+        if devices.is_empty() {
+            return;
+        }
+
+        let container = DeviceInventoryContainer {
+            ts: last_ts,
+            devices,
+        };
+        if let Err(err) = self
+            .inventory_recipient
+            .do_send(Container::DeviceInventory(container))
+        {
+            error!("Error sending message to recipient: {err}");
+        }
+    }
+}
+
+impl Actor for DeviceActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("DeviceActor started");
+        ctx.run_interval(Duration::from_secs(INVENTORY_INTERVAL), |act, _ctx| {
+            act.send_inventory();
+        });
+    }
+}
+
+impl Handler<ServerResponseContainer> for DeviceActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: ServerResponseContainer, _: &mut Self::Context) {
+        self.update(&msg);
+
+        // Forward the message to the next actor in the chain
+        match self.recipient.do_send(msg) {
+            Ok(_) => (),
+            Err(err) => {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
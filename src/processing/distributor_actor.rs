@@ -0,0 +1,87 @@
+use actix::prelude::*;
+use ogn_parser::{AprsData, ServerResponse};
+
+use crate::{
+    containers::{containers::Container, weather_container::is_weather},
+    messages::server_response_container::ServerResponseContainer,
+};
+
+#[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PositionStream {
+    Raw,
+    Fused,
+    Both,
+}
+
+pub struct Output {
+    pub recipient: Recipient<ServerResponseContainer>,
+    pub container_recipient: Recipient<Container>,
+
+    pub position_stream: PositionStream,
+}
+
+pub struct DistributorActor {
+    pub outputs: Vec<Output>,
+}
+
+impl DistributorActor {
+    pub fn new(outputs: Vec<Output>) -> Self {
+        DistributorActor { outputs }
+    }
+}
+
+impl Actor for DistributorActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _: &mut Self::Context) {
+        info!("DistributorActor started");
+    }
+}
+
+impl Handler<ServerResponseContainer> for DistributorActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: ServerResponseContainer, _: &mut Self::Context) {
+        let is_position = matches!(
+            &msg.server_response,
+            ServerResponse::AprsPacket(packet) if matches!(&packet.data, AprsData::Position(position) if !is_weather(position))
+        );
+
+        let recipients = self
+            .outputs
+            .iter()
+            .filter(|output| !is_position || output.position_stream != PositionStream::Fused)
+            .map(|output| &output.recipient)
+            .collect::<Vec<_>>();
+
+        // Every output target gets its own copy, the last one gets the original
+        if let Some((last, others)) = recipients.split_last() {
+            for recipient in others {
+                if let Err(err) = recipient.do_send(msg.clone()) {
+                    error!("Error sending message to recipient: {err}");
+                }
+            }
+            if let Err(err) = last.do_send(msg) {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
+
+impl Handler<Container> for DistributorActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: Container, _: &mut Self::Context) {
+        let is_fused_position = matches!(msg, Container::FusedPosition(_));
+
+        for output in self
+            .outputs
+            .iter()
+            .filter(|output| !is_fused_position || output.position_stream != PositionStream::Raw)
+        {
+            if let Err(err) = output.container_recipient.do_send(msg.clone()) {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
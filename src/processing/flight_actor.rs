@@ -0,0 +1,471 @@
+use std::{collections::HashMap, time::Duration};
+
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use ogn_parser::{AprsData, AprsPacket, AprsPosition, ServerResponse};
+
+use crate::{
+    containers::{
+        address_type::AddressType,
+        aircraft_type::AircraftType,
+        containers::Container,
+        flight_container::{FlightContainer, LaunchMethod},
+        protocol::Protocol,
+        units::{FOOT, Units},
+    },
+    messages::server_response_container::ServerResponseContainer,
+    utils::geo,
+};
+
+// thresholds for the flight state machine
+const TAKEOFF_SPEED: u16 = 32; // [kt] ~60km/h
+const LANDING_SPEED: u16 = 27; // [kt] ~50km/h
+const GROUND_SPEED: u16 = 5; // [kt] below this speed the aircraft is standing on ground
+const TAKEOFF_HEIGHT: f64 = 50.0; // [m] above ground, for slow aircraft (e.g. paraglider)
+const LANDING_HEIGHT: f64 = 100.0; // [m] above ground
+const LANDING_CLIMB_RATE: i16 = 400; // [fpm] ~2m/s
+const TIMEOUT: i64 = 30 * 60; // [s] aircraft not seen for this time is removed
+
+// thresholds for the launch classification
+const GLIDER: u8 = 1;
+const TOW_PLANES: [u8; 2] = [2, 8]; // tow plane, powered aircraft
+const PAIRING_TIME: i64 = 30; // [s] max. time between takeoff of glider and tow plane
+const PAIRING_DISTANCE: f64 = 500.0; // [m] max. distance between takeoff of glider and tow plane
+const RELEASE_DISTANCE: f64 = 300.0; // [m] glider and tow plane separated
+const MAX_TOW_TIME: i64 = 20 * 60; // [s]
+const MAX_LAUNCH_TIME: i64 = 10 * 60; // [s] for winch and self launch
+const LAUNCH_END_CLIMB_RATE: i16 = 200; // [fpm] ~1m/s
+const WINCH_CLIMB_RATE: i16 = 1200; // [fpm] ~6m/s
+const WINCH_MAX_HEIGHT: u32 = 2600; // [ft] ~800m
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlightState {
+    // first seen slow but moving and without height above ground, e.g. a paraglider in flight
+    Unknown,
+    OnGround,
+    Airborne,
+}
+
+pub struct Aircraft {
+    pub state: FlightState,
+    pub last_ts: DateTime<Utc>,
+
+    pub location: (f64, f64),
+
+    // altitude [ft] where the aircraft has been standing on ground at last
+    pub ground_altitude: Option<u32>,
+
+    pub flight: Option<FlightContainer>,
+    pub launch: Option<Launch>,
+}
+
+// a glider launch which is not classified yet
+pub struct Launch {
+    pub takeoff_altitude: u32,
+    pub max_climb_rate: i16,
+    pub tow_plane: Option<String>,
+}
+
+pub struct FlightActor {
+    pub recipient: Recipient<ServerResponseContainer>,
+    pub flight_recipient: Recipient<Container>,
+
+    pub aircrafts: HashMap<String, Aircraft>,
+    pub last_ts: Option<DateTime<Utc>>,
+}
+
+impl FlightActor {
+    pub fn new(
+        recipient: Recipient<ServerResponseContainer>,
+        flight_recipient: Recipient<Container>,
+    ) -> Self {
+        FlightActor {
+            recipient,
+            flight_recipient,
+
+            aircrafts: HashMap::new(),
+            last_ts: None,
+        }
+    }
+
+    fn new_flight(packet: &AprsPacket, position: &AprsPosition) -> FlightContainer {
+        let id = position.comment.id.as_ref();
+
+        FlightContainer {
+            units: Units::Aviation,
+
+            src_call: packet.from.clone(),
+            dst_call: packet.to.clone(),
+            protocol: Protocol::from_dst_call(&packet.to.to_string()),
+
+            address_type: id.map(|id| id.address_type),
+            aircraft_type: id.map(|id| id.aircraft_type),
+            address_type_name: id.and_then(|id| AddressType::new(id.address_type)),
+            aircraft_type_name: id.map(|id| AircraftType::from(id.aircraft_type)),
+            address: id.map(|id| id.address),
+
+            takeoff_ts: None,
+            takeoff_location: None,
+            takeoff_airfield: None,
+            takeoff_runway: None,
+
+            landing_ts: None,
+            landing_location: None,
+            landing_airfield: None,
+            landing_runway: None,
+
+            launch_method: None,
+            tow_plane: None,
+            release_height: None,
+
+            max_altitude: position.comment.altitude,
+            duration: None,
+        }
+    }
+
+    fn send(&self, flight: FlightContainer) {
+        if let Err(err) = self.flight_recipient.do_send(Container::Flight(flight)) {
+            error!("Error sending message to recipient: {err}");
+        }
+    }
+
+    // runway designator from the course, e.g. 266° -> "27" (course 0 means unknown)
+    fn runway(course: Option<u16>) -> Option<String> {
+        course.filter(|course| *course > 0).map(|course| {
+            let runway = ((course as f64 / 10.0).round() as u16 + 35) % 36 + 1;
+            format!("{runway:02}")
+        })
+    }
+
+    fn update(
+        &mut self,
+        msg: &ServerResponseContainer,
+        packet: &AprsPacket,
+        position: &AprsPosition,
+        ts: DateTime<Utc>,
+    ) {
+        let (Some(speed), Some(altitude)) = (position.comment.speed, position.comment.altitude)
+        else {
+            return;
+        };
+        let location = (*position.longitude, *position.latitude);
+
+        let aircraft = match self.aircrafts.get_mut(&packet.from.call) {
+            Some(aircraft) => aircraft,
+            None => {
+                // we don't know how the aircraft came here, so the first position just defines the state
+                let height = msg
+                    .airfield_elevation
+                    .map(|elevation| altitude as f64 * FOOT - elevation);
+                let state = if speed < GROUND_SPEED {
+                    FlightState::OnGround
+                } else if speed > TAKEOFF_SPEED
+                    || height.is_some_and(|height| height > TAKEOFF_HEIGHT)
+                {
+                    FlightState::Airborne
+                } else {
+                    FlightState::Unknown
+                };
+                self.aircrafts.insert(
+                    packet.from.call.clone(),
+                    Aircraft {
+                        state,
+                        last_ts: ts,
+                        location,
+                        ground_altitude: (speed < GROUND_SPEED).then_some(altitude),
+                        flight: (state == FlightState::Airborne)
+                            .then(|| Self::new_flight(packet, position)),
+                        launch: None,
+                    },
+                );
+                return;
+            }
+        };
+
+        // skip copies from other receivers and messages out of order
+        if ts <= aircraft.last_ts {
+            return;
+        }
+        aircraft.last_ts = ts;
+        aircraft.location = location;
+
+        // height above ground, there is no terrain model: the elevation is taken from the airfield (if known)
+        // or from the altitude where the aircraft stood at last
+        let height = match msg.airfield_elevation {
+            Some(elevation) => Some(altitude as f64 * FOOT - elevation),
+            None => aircraft
+                .ground_altitude
+                .map(|ground_altitude| (altitude as f64 - ground_altitude as f64) * FOOT),
+        };
+        let runway = msg
+            .airfield
+            .as_ref()
+            .and_then(|_| Self::runway(position.comment.course));
+
+        let mut landed_flight = None;
+        match aircraft.state {
+            FlightState::Unknown => {
+                if speed < GROUND_SPEED {
+                    aircraft.state = FlightState::OnGround;
+                    aircraft.ground_altitude = Some(altitude);
+                } else if speed > TAKEOFF_SPEED
+                    || height.is_some_and(|height| height > TAKEOFF_HEIGHT)
+                {
+                    // we didn't see the takeoff, so the flight has none
+                    aircraft.state = FlightState::Airborne;
+                    aircraft.flight = Some(Self::new_flight(packet, position));
+                }
+            }
+            FlightState::OnGround => {
+                if speed < GROUND_SPEED {
+                    aircraft.ground_altitude = Some(altitude);
+                }
+
+                if speed > TAKEOFF_SPEED || height.is_some_and(|height| height > TAKEOFF_HEIGHT) {
+                    let mut flight = Self::new_flight(packet, position);
+                    flight.takeoff_ts = Some(ts);
+                    flight.takeoff_location = Some(location);
+                    flight.takeoff_airfield = msg.airfield.clone();
+                    flight.takeoff_runway = runway;
+
+                    debug!("Takeoff of {} at {}", packet.from, ts);
+                    aircraft.state = FlightState::Airborne;
+                    aircraft.launch = (flight.aircraft_type == Some(GLIDER)).then_some(Launch {
+                        takeoff_altitude: altitude,
+                        max_climb_rate: 0,
+                        tow_plane: None,
+                    });
+                    aircraft.flight = Some(flight);
+                }
+            }
+            FlightState::Airborne => {
+                let flight = aircraft
+                    .flight
+                    .get_or_insert_with(|| Self::new_flight(packet, position));
+                flight.max_altitude = flight.max_altitude.max(Some(altitude));
+
+                // without height above ground only an aircraft standing still has landed
+                let is_low = match height {
+                    Some(height) => height < LANDING_HEIGHT,
+                    None => speed < GROUND_SPEED,
+                };
+                let is_level = position
+                    .comment
+                    .climb_rate
+                    .is_none_or(|climb_rate| climb_rate.abs() < LANDING_CLIMB_RATE);
+                if speed < LANDING_SPEED && is_low && is_level {
+                    flight.landing_ts = Some(ts);
+                    flight.landing_location = Some(location);
+                    flight.landing_airfield = msg.airfield.clone();
+                    flight.landing_runway = runway;
+                    flight.duration = flight
+                        .takeoff_ts
+                        .map(|takeoff_ts| ts.signed_duration_since(takeoff_ts).num_seconds());
+
+                    // launch not finished (e.g. rope break), so we classify with what we have
+                    if let Some(launch) = aircraft.launch.take() {
+                        let release_height = flight.max_altitude.map(|max_altitude| {
+                            max_altitude.saturating_sub(launch.takeoff_altitude)
+                        });
+                        flight.launch_method = Some(if launch.tow_plane.is_some() {
+                            LaunchMethod::Aerotow
+                        } else if launch.max_climb_rate > WINCH_CLIMB_RATE {
+                            LaunchMethod::Winch
+                        } else {
+                            LaunchMethod::Unknown
+                        });
+                        flight.tow_plane = launch.tow_plane;
+                        flight.release_height = release_height;
+                    }
+
+                    debug!("Landing of {} at {}", packet.from, ts);
+                    aircraft.state = FlightState::OnGround;
+                    aircraft.ground_altitude = Some(altitude);
+                    landed_flight = aircraft.flight.take();
+                }
+            }
+        }
+
+        match landed_flight {
+            Some(flight) => self.send(flight),
+            None => {
+                self.update_launch(&packet.from.call, altitude, position.comment.climb_rate, ts)
+            }
+        }
+    }
+
+    // tow plane which took off at the same time and place as the glider
+    fn find_tow_plane(
+        &self,
+        glider: &str,
+        takeoff_ts: DateTime<Utc>,
+        takeoff_location: (f64, f64),
+    ) -> Option<String> {
+        self.aircrafts
+            .iter()
+            .filter(|(name, _)| *name != glider)
+            .filter_map(|(name, aircraft)| aircraft.flight.as_ref().map(|flight| (name, flight)))
+            .filter(|(_, flight)| {
+                flight
+                    .aircraft_type
+                    .is_some_and(|aircraft_type| TOW_PLANES.contains(&aircraft_type))
+            })
+            .filter_map(|(name, flight)| {
+                let (ts, (longitude, latitude)) = flight.takeoff_ts.zip(flight.takeoff_location)?;
+                let delta = ts.signed_duration_since(takeoff_ts).num_seconds().abs();
+                let distance =
+                    geo::distance(latitude, longitude, takeoff_location.1, takeoff_location.0);
+                (delta <= PAIRING_TIME && distance <= PAIRING_DISTANCE).then_some((name, delta))
+            })
+            .min_by_key(|(_, delta)| *delta)
+            .map(|(name, _)| name.clone())
+    }
+
+    // classify the launch method of a glider as soon as the launch is finished
+    fn update_launch(
+        &mut self,
+        glider: &str,
+        altitude: u32,
+        climb_rate: Option<i16>,
+        ts: DateTime<Utc>,
+    ) {
+        let Some(aircraft) = self.aircrafts.get(glider) else {
+            return;
+        };
+        let (Some(launch), Some(flight)) = (&aircraft.launch, &aircraft.flight) else {
+            return;
+        };
+        let (Some(takeoff_ts), Some(takeoff_location)) =
+            (flight.takeoff_ts, flight.takeoff_location)
+        else {
+            return;
+        };
+
+        let elapsed = ts.signed_duration_since(takeoff_ts).num_seconds();
+        let height = altitude.saturating_sub(launch.takeoff_altitude);
+        let max_climb_rate = launch.max_climb_rate.max(climb_rate.unwrap_or_default());
+
+        let tow_plane = match &launch.tow_plane {
+            Some(tow_plane) => Some(tow_plane.clone()),
+            None if elapsed <= PAIRING_TIME => {
+                self.find_tow_plane(glider, takeoff_ts, takeoff_location)
+            }
+            None => None,
+        };
+
+        let launch_method = match &tow_plane {
+            Some(tow_plane) => {
+                let is_released = self.aircrafts.get(tow_plane).is_none_or(|tow| {
+                    let (longitude, latitude) = tow.location;
+                    geo::distance(
+                        latitude,
+                        longitude,
+                        aircraft.location.1,
+                        aircraft.location.0,
+                    ) > RELEASE_DISTANCE
+                });
+                (is_released || elapsed > MAX_TOW_TIME)
+                    .then_some((LaunchMethod::Aerotow, Some(height)))
+            }
+            None if elapsed > PAIRING_TIME => {
+                let is_climbing =
+                    climb_rate.is_some_and(|climb_rate| climb_rate > LAUNCH_END_CLIMB_RATE);
+                if is_climbing && elapsed <= MAX_LAUNCH_TIME {
+                    None
+                } else if max_climb_rate > WINCH_CLIMB_RATE && height < WINCH_MAX_HEIGHT {
+                    Some((LaunchMethod::Winch, Some(height)))
+                } else if max_climb_rate <= WINCH_CLIMB_RATE && height >= WINCH_MAX_HEIGHT {
+                    // powered climb: the launch only ends when the climb stops, so the glider climbed
+                    // without interruption beyond winch height and without the steep climb of a winch
+                    Some((LaunchMethod::SelfLaunch, Some(height)))
+                } else {
+                    Some((LaunchMethod::Unknown, None))
+                }
+            }
+            None => None,
+        };
+
+        let Some(aircraft) = self.aircrafts.get_mut(glider) else {
+            return;
+        };
+        match launch_method {
+            Some((launch_method, release_height)) => {
+                if let Some(flight) = aircraft.flight.as_mut() {
+                    debug!(
+                        "Launch of {glider} classified as {}",
+                        launch_method.as_str()
+                    );
+                    flight.launch_method = Some(launch_method);
+                    flight.tow_plane = tow_plane;
+                    flight.release_height = release_height;
+                }
+                aircraft.launch = None;
+            }
+            None => {
+                if let Some(launch) = aircraft.launch.as_mut() {
+                    launch.max_climb_rate = max_climb_rate;
+                    launch.tow_plane = tow_plane;
+                }
+            }
+        }
+    }
+
+    // remove aircrafts we didn't see for a long time, flights without landing are sent as they are
+    fn remove_outdated(&mut self) {
+        let Some(last_ts) = self.last_ts else {
+            return;
+        };
+
+        let mut outdated_flights = vec![];
+        self.aircrafts.retain(|_, aircraft| {
+            if last_ts
+                .signed_duration_since(aircraft.last_ts)
+                .num_seconds()
+                > TIMEOUT
+            {
+                outdated_flights.extend(aircraft.flight.take());
+                false
+            } else {
+                true
+            }
+        });
+
+        for flight in outdated_flights {
+            self.send(flight);
+        }
+    }
+}
+
+impl Actor for FlightActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("FlightActor started");
+        ctx.run_interval(Duration::from_secs(60), |act, _ctx| {
+            act.remove_outdated();
+        });
+    }
+}
+
+impl Handler<ServerResponseContainer> for FlightActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: ServerResponseContainer, _: &mut Self::Context) {
+        if let ServerResponse::AprsPacket(packet) = &msg.server_response
+            && let AprsData::Position(position) = &packet.data
+            && position.comment.id.is_some()
+        {
+            let ts = msg.receiver_ts.unwrap_or(msg.ts);
+            self.last_ts = self.last_ts.max(Some(ts));
+            self.update(&msg, packet, position, ts);
+        }
+
+        // Forward the message to the next actor in the chain
+        match self.recipient.do_send(msg) {
+            Ok(_) => (),
+            Err(err) => {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+use actix::prelude::*;
+use chrono::{DateTime, TimeDelta, Utc};
+use ogn_parser::{AprsData, ServerResponse};
+
+use crate::{
+    containers::{
+        containers::Container, fused_position_container::FusedPositionContainer,
+        position_container::PositionContainer,
+    },
+    messages::server_response_container::ServerResponseContainer,
+};
+
+// copies of the same transmission have the same sender and position...
+type TransmissionKey = (String, String);
+
+// ... and (almost) the same receiver time - receivers with a bad clock may differ by some seconds
+pub struct Transmission {
+    pub id: u64,
+    pub ts: DateTime<Utc>,
+    pub created: Instant,
+    pub receiver_time: String,
+    pub receiver_ts: Option<DateTime<Utc>>,
+    pub copies: Vec<PositionContainer>,
+}
+
+pub struct FusionActor {
+    pub recipient: Recipient<ServerResponseContainer>,
+    pub fused_recipient: Recipient<Container>,
+
+    pub window: Duration,
+    pub transmissions: HashMap<TransmissionKey, Vec<Transmission>>,
+
+    // pending transmissions ordered by message time, so only the expired ones are touched per message
+    pub deadlines: BTreeMap<(DateTime<Utc>, u64), TransmissionKey>,
+    pub next_id: u64,
+}
+
+impl FusionActor {
+    pub fn new(
+        recipient: Recipient<ServerResponseContainer>,
+        fused_recipient: Recipient<Container>,
+        window: Duration,
+    ) -> Self {
+        FusionActor {
+            recipient,
+            fused_recipient,
+
+            window,
+            transmissions: HashMap::new(),
+
+            deadlines: BTreeMap::new(),
+            next_id: 0,
+        }
+    }
+
+    fn is_same_time(
+        &self,
+        transmission: &Transmission,
+        receiver_time: &str,
+        receiver_ts: Option<DateTime<Utc>>,
+    ) -> bool {
+        match (transmission.receiver_ts, receiver_ts) {
+            (Some(previous), Some(actual)) => actual
+                .signed_duration_since(previous)
+                .abs()
+                .to_std()
+                .is_ok_and(|delta| delta <= self.window),
+            _ => transmission.receiver_time == receiver_time,
+        }
+    }
+
+    fn send(&self, copies: &[PositionContainer]) {
+        let fused = FusedPositionContainer::new(copies);
+        if let Err(err) = self
+            .fused_recipient
+            .do_send(Container::FusedPosition(fused))
+        {
+            error!("Error sending message to recipient: {err}");
+        }
+    }
+
+    // emit the transmissions which are older than the window by message time (e.g. a replayed log)
+    fn expire(&mut self, now: DateTime<Utc>) {
+        let cutoff = now - TimeDelta::from_std(self.window).unwrap_or(TimeDelta::MAX);
+        while let Some(entry) = self.deadlines.first_entry()
+            && entry.key().0 < cutoff
+        {
+            let ((_, id), key) = entry.remove_entry();
+            // the transmission may have been flushed by wall clock already
+            let Some(transmissions) = self.transmissions.get_mut(&key) else {
+                continue;
+            };
+            let Some(index) = transmissions
+                .iter()
+                .position(|transmission| transmission.id == id)
+            else {
+                continue;
+            };
+            let transmission = transmissions.remove(index);
+            if transmissions.is_empty() {
+                self.transmissions.remove(&key);
+            }
+            self.send(&transmission.copies);
+        }
+    }
+
+    // emit all transmissions which are older than the window by wall clock (e.g. a sender which is not received anymore)
+    fn flush(&mut self) {
+        let window = self.window;
+        let mut expired = vec![];
+        self.transmissions.retain(|_, transmissions| {
+            let (old, new): (Vec<_>, Vec<_>) = transmissions
+                .drain(..)
+                .partition(|transmission| transmission.created.elapsed() > window);
+            expired.extend(old);
+            *transmissions = new;
+            !transmissions.is_empty()
+        });
+
+        for transmission in expired {
+            self.deadlines.remove(&(transmission.ts, transmission.id));
+            self.send(&transmission.copies);
+        }
+    }
+}
+
+impl Actor for FusionActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("FusionActor started");
+        ctx.run_interval(Duration::from_secs(1), |act, _ctx| {
+            act.flush();
+        });
+    }
+}
+
+impl Handler<ServerResponseContainer> for FusionActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: ServerResponseContainer, _: &mut Self::Context) {
+        if let ServerResponse::AprsPacket(packet) = &msg.server_response
+            && let AprsData::Position(position) = &packet.data
+        {
+            let ts = msg.ts;
+            self.expire(ts);
+
+            if let Container::Position(copy) = Container::from(msg.clone()) {
+                match &position.timestamp {
+                    Some(timestamp) => {
+                        let key = (
+                            packet.from.to_string(),
+                            format!(
+                                "{:.6} {:.6} {:?}",
+                                *position.latitude, *position.longitude, position.comment.altitude
+                            ),
+                        );
+                        let receiver_time = timestamp.to_string();
+                        let receiver_ts = msg.receiver_ts;
+
+                        let index = self.transmissions.get(&key).and_then(|transmissions| {
+                            // a receiver can't receive the same transmission twice (e.g. a sender on ground with constant position)
+                            transmissions.iter().position(|transmission| {
+                                self.is_same_time(transmission, &receiver_time, receiver_ts)
+                                    && transmission
+                                        .copies
+                                        .iter()
+                                        .all(|other| other.receiver != copy.receiver)
+                            })
+                        });
+                        let transmission = index
+                            .and_then(|index| self.transmissions.get_mut(&key)?.get_mut(index));
+                        match transmission {
+                            Some(transmission) => transmission.copies.push(copy),
+                            None => {
+                                let id = self.next_id;
+                                self.next_id += 1;
+                                self.deadlines.insert((ts, id), key.clone());
+                                self.transmissions
+                                    .entry(key)
+                                    .or_default()
+                                    .push(Transmission {
+                                        id,
+                                        ts,
+                                        created: Instant::now(),
+                                        receiver_time,
+                                        receiver_ts,
+                                        copies: vec![copy],
+                                    });
+                            }
+                        }
+                    }
+                    None => {
+                        // without receiver time we can't identify copies, so it is a transmission on its own
+                        self.send(&[copy]);
+                    }
+                }
+            }
+        }
+
+        // Forward the message to the next actor in the chain
+        match self.recipient.do_send(msg) {
+            Ok(_) => (),
+            Err(err) => {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
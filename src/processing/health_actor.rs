@@ -0,0 +1,311 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    str::FromStr,
+    time::Duration,
+};
+
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use ogn_parser::{AprsData, ServerResponse, StatusComment};
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::{
+    containers::{alert_container::AlertContainer, containers::Container, protocol::Protocol},
+    messages::server_response_container::ServerResponseContainer,
+};
+
+// values of the status comment which can be checked
+const FIELDS: [&str; 11] = [
+    "cpu_load",
+    "ram_free",
+    "cpu_temperature",
+    "voltage",
+    "amperage",
+    "ntp_offset",
+    "ntp_correction",
+    "noise",
+    "latency",
+    "senders",
+    "visible_senders",
+];
+
+// rules if none are given on the command line
+const DEFAULT_RULES: [&str; 6] = [
+    "cpu_load>2",
+    "ram_free<20",
+    "cpu_temperature>75",
+    "ntp_offset>50",
+    "ntp_offset<-50",
+    "noise>10",
+];
+
+const HEALTH_INTERVAL: u64 = 60; // [s] beacon gaps are checked periodically
+const TIMEOUT: i64 = 24 * 60 * 60; // [s] receivers not seen for this time are removed
+
+const BEACON_GAP: &str = "beacon_gap";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Above,
+    Below,
+}
+
+// format: <FIELD><'>' or '<'><VALUE>, e.g. "cpu_temperature>75" or "ram_free<20"
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthRule {
+    pub field: String,
+    pub comparison: Comparison,
+    pub threshold: f64,
+}
+
+impl FromStr for HealthRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (field, comparison, threshold) = if let Some((field, threshold)) = s.split_once('>') {
+            (field, Comparison::Above, threshold)
+        } else if let Some((field, threshold)) = s.split_once('<') {
+            (field, Comparison::Below, threshold)
+        } else {
+            return Err(format!(
+                "expected <FIELD>'>'<VALUE> or <FIELD>'<'<VALUE>, got '{s}'"
+            ));
+        };
+
+        let field = field.trim();
+        if !FIELDS.contains(&field) {
+            return Err(format!(
+                "unknown field '{field}', possible values: {}",
+                FIELDS.join(", ")
+            ));
+        }
+        let threshold = threshold
+            .trim()
+            .parse::<f64>()
+            .map_err(|err| format!("invalid value '{threshold}': {err}"))?;
+
+        Ok(HealthRule {
+            field: field.to_string(),
+            comparison,
+            threshold,
+        })
+    }
+}
+
+impl fmt::Display for HealthRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let comparison = match self.comparison {
+            Comparison::Above => '>',
+            Comparison::Below => '<',
+        };
+        write!(f, "{}{comparison}{}", self.field, self.threshold)
+    }
+}
+
+impl HealthRule {
+    pub fn defaults() -> Vec<HealthRule> {
+        DEFAULT_RULES
+            .iter()
+            .map(|rule| rule.parse().unwrap())
+            .collect()
+    }
+
+    fn value(&self, comment: &StatusComment) -> Option<f64> {
+        match self.field.as_str() {
+            "cpu_load" => comment.cpu_load.and_then(|value| value.to_f64()),
+            "ram_free" => comment.ram_free.and_then(|value| value.to_f64()),
+            "cpu_temperature" => comment.cpu_temperature.and_then(|value| value.to_f64()),
+            "voltage" => comment.voltage.and_then(|value| value.to_f64()),
+            "amperage" => comment.amperage.and_then(|value| value.to_f64()),
+            "ntp_offset" => comment.ntp_offset.and_then(|value| value.to_f64()),
+            "ntp_correction" => comment.ntp_correction.and_then(|value| value.to_f64()),
+            "noise" => comment.noise.and_then(|value| value.to_f64()),
+            "latency" => comment.latency.and_then(|value| value.to_f64()),
+            "senders" => comment.senders.map(f64::from),
+            "visible_senders" => comment.visible_senders.map(f64::from),
+            _ => None,
+        }
+    }
+
+    fn is_violated(&self, value: f64) -> bool {
+        match self.comparison {
+            Comparison::Above => value > self.threshold,
+            Comparison::Below => value < self.threshold,
+        }
+    }
+}
+
+pub struct HealthActor {
+    pub recipient: Recipient<ServerResponseContainer>,
+    pub alert_recipient: Recipient<Container>,
+    pub rules: Vec<HealthRule>,
+    pub max_gap: i64,
+
+    pub receivers: HashMap<String, DateTime<Utc>>,
+    pub active_alerts: HashSet<(String, String)>,
+    pub last_ts: Option<DateTime<Utc>>,
+}
+
+impl HealthActor {
+    pub fn new(
+        recipient: Recipient<ServerResponseContainer>,
+        alert_recipient: Recipient<Container>,
+        rules: Vec<HealthRule>,
+        max_gap: i64,
+    ) -> Self {
+        HealthActor {
+            recipient,
+            alert_recipient,
+            rules,
+            max_gap,
+
+            receivers: HashMap::new(),
+            active_alerts: HashSet::new(),
+            last_ts: None,
+        }
+    }
+
+    // send an alert only if the state of the rule changed
+    fn set_alert(
+        &mut self,
+        ts: DateTime<Utc>,
+        receiver: &str,
+        rule: String,
+        value: Option<f64>,
+        active: bool,
+        message: String,
+    ) {
+        let key = (receiver.to_string(), rule.clone());
+        let changed = if active {
+            self.active_alerts.insert(key)
+        } else {
+            self.active_alerts.remove(&key)
+        };
+        if !changed {
+            return;
+        }
+
+        let alert = AlertContainer {
+            ts,
+            receiver: receiver.to_string(),
+            rule,
+            value,
+            active,
+            message,
+        };
+        if let Err(err) = self.alert_recipient.do_send(Container::Alert(alert)) {
+            error!("Error sending message to recipient: {err}");
+        }
+    }
+
+    // gaps are measured with the ingest time: the receiver time depends on the clock of the (possibly bad) receiver
+    fn update(&mut self, msg: &ServerResponseContainer) {
+        let ts = msg.ts;
+        self.last_ts = self.last_ts.max(Some(ts));
+
+        let ServerResponse::AprsPacket(packet) = &msg.server_response else {
+            return;
+        };
+        if Protocol::from_dst_call(&packet.to.to_string()) != Protocol::Receiver {
+            return;
+        }
+
+        let receiver = packet.from.to_string();
+        if let Some(last_ts) = self.receivers.insert(receiver.clone(), ts) {
+            let gap = ts.signed_duration_since(last_ts).num_seconds();
+            self.set_alert(
+                ts,
+                &receiver,
+                BEACON_GAP.to_string(),
+                Some(gap as f64),
+                false,
+                format!("{receiver} is back after {gap}s"),
+            );
+        }
+
+        if let AprsData::Status(status) = &packet.data {
+            for rule in self.rules.clone() {
+                let Some(value) = rule.value(&status.comment) else {
+                    continue;
+                };
+                let active = rule.is_violated(value);
+                let message = if active {
+                    format!("{receiver}: {} is {value} ({rule})", rule.field)
+                } else {
+                    format!("{receiver}: {} is {value} again", rule.field)
+                };
+                self.set_alert(
+                    ts,
+                    &receiver,
+                    rule.to_string(),
+                    Some(value),
+                    active,
+                    message,
+                );
+            }
+        }
+    }
+
+    // raise an alert for receivers which went silent, remove receivers not seen for a long time
+    fn check_gaps(&mut self) {
+        let Some(last_ts) = self.last_ts else {
+            return;
+        };
+
+        let silent = self
+            .receivers
+            .iter()
+            .map(|(receiver, ts)| {
+                (
+                    receiver.clone(),
+                    last_ts.signed_duration_since(*ts).num_seconds(),
+                )
+            })
+            .filter(|(_, gap)| *gap > self.max_gap)
+            .collect::<Vec<_>>();
+        for (receiver, gap) in silent {
+            self.set_alert(
+                last_ts,
+                &receiver,
+                BEACON_GAP.to_string(),
+                Some(gap as f64),
+                true,
+                format!("{receiver} has been silent for {gap}s"),
+            );
+        }
+
+        self.receivers
+            .retain(|_, ts| last_ts.signed_duration_since(*ts).num_seconds() <= TIMEOUT);
+        let receivers = &self.receivers;
+        self.active_alerts
+            .retain(|(receiver, _)| receivers.contains_key(receiver));
+    }
+}
+
+impl Actor for HealthActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("HealthActor started");
+        ctx.run_interval(Duration::from_secs(HEALTH_INTERVAL), |act, _ctx| {
+            act.check_gaps();
+        });
+    }
+}
+
+impl Handler<ServerResponseContainer> for HealthActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: ServerResponseContainer, _: &mut Self::Context) {
+        self.update(&msg);
+
+        // Forward the message to the next actor in the chain
+        match self.recipient.do_send(msg) {
+            Ok(_) => (),
+            Err(err) => {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use std::{collections::HashMap, time::Duration};
+
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use ogn_parser::{AprsData, ServerResponse};
+
+use crate::{
+    containers::{
+        containers::Container,
+        protocol::Protocol,
+        receiver_inventory_container::{
+            ReceiverInventory, ReceiverInventoryContainer, ReceiverVersionContainer,
+        },
+        units::Units,
+    },
+    messages::server_response_container::ServerResponseContainer,
+};
+
+const INVENTORY_INTERVAL: u64 = 5 * 60; // [s] the inventory is sent periodically
+
+pub struct InventoryActor {
+    pub recipient: Recipient<ServerResponseContainer>,
+    pub inventory_recipient: Recipient<Container>,
+
+    pub receivers: HashMap<String, ReceiverInventory>,
+    pub last_ts: Option<DateTime<Utc>>,
+}
+
+impl InventoryActor {
+    pub fn new(
+        recipient: Recipient<ServerResponseContainer>,
+        inventory_recipient: Recipient<Container>,
+    ) -> Self {
+        InventoryActor {
+            recipient,
+            inventory_recipient,
+
+            receivers: HashMap::new(),
+            last_ts: None,
+        }
+    }
+
+    fn send(&self, container: Container) {
+        if let Err(err) = self.inventory_recipient.do_send(container) {
+            error!("Error sending message to recipient: {err}");
+        }
+    }
+
+    fn update(&mut self, msg: &ServerResponseContainer) {
+        let ServerResponse::AprsPacket(packet) = &msg.server_response else {
+            return;
+        };
+        if Protocol::from_dst_call(&packet.to.to_string()) != Protocol::Receiver {
+            return;
+        }
+
+        let ts = msg.receiver_ts.unwrap_or(msg.ts);
+        let name = packet.from.to_string();
+        self.last_ts = self.last_ts.max(Some(ts));
+        let receiver = self
+            .receivers
+            .entry(name.clone())
+            .or_insert_with(|| ReceiverInventory {
+                receiver: name.clone(),
+                units: Units::Aviation,
+                location: None,
+                altitude: None,
+                version: None,
+                platform: None,
+                first_seen: ts,
+                last_seen: ts,
+            });
+        receiver.first_seen = receiver.first_seen.min(ts);
+        receiver.last_seen = receiver.last_seen.max(ts);
+
+        match &packet.data {
+            AprsData::Position(position) => {
+                receiver.location = Some((*position.longitude, *position.latitude));
+                receiver.altitude = position.comment.altitude.or(receiver.altitude);
+            }
+            AprsData::Status(status) => {
+                if status.comment.platform.is_some() {
+                    receiver.platform = status.comment.platform.clone();
+                }
+                // the first version of a receiver is no change: it may be the same as before the start of the client
+                if let Some(version) = &status.comment.version
+                    && receiver.version.as_ref() != Some(version)
+                    && let Some(previous_version) = receiver.version.replace(version.clone())
+                {
+                    let container = ReceiverVersionContainer {
+                        ts,
+                        receiver: name,
+                        previous_version,
+                        version: version.clone(),
+                        platform: receiver.platform.clone(),
+                    };
+                    self.send(Container::ReceiverVersion(container));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn send_inventory(&self) {
+        let Some(last_ts) = self.last_ts else {
+            return;
+        };
+
+        let mut receivers = self.receivers.values().cloned().collect::<Vec<_>>();
+        receivers.sort_by(|a, b| a.receiver.cmp(&b.receiver));
+        self.send(Container::ReceiverInventory(ReceiverInventoryContainer {
+            ts: last_ts,
+            units: Units::Aviation,
+            receivers,
+        }));
+    }
+}
+
+impl Actor for InventoryActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("InventoryActor started");
+        ctx.run_interval(Duration::from_secs(INVENTORY_INTERVAL), |act, _ctx| {
+            act.send_inventory();
+        });
+    }
+}
+
+impl Handler<ServerResponseContainer> for InventoryActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: ServerResponseContainer, _: &mut Self::Context) {
+        self.update(&msg);
+
+        // Forward the message to the next actor in the chain
+        match self.recipient.do_send(msg) {
+            Ok(_) => (),
+            Err(err) => {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
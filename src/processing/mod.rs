@@ -1,3 +1,20 @@
+pub mod airfield_actor;
+pub mod circling_actor;
+pub mod coverage_actor;
+pub mod decimation_actor;
+pub mod device_actor;
+pub mod distributor_actor;
 pub mod filter_actor;
+pub mod flight_actor;
+pub mod fusion_actor;
+pub mod health_actor;
+pub mod inventory_actor;
 pub mod parser_actor;
+pub mod polar_actor;
+pub mod qnh_actor;
+pub mod sensitivity_actor;
+pub mod state_actor;
+pub mod thermal_actor;
+pub mod timing_actor;
 pub mod validation_actor;
+pub mod wind_actor;
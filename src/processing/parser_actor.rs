@@ -34,16 +34,8 @@ impl Handler<OGNMessage> for ParserActor {
     fn handle(&mut self, msg: OGNMessage, _: &mut Context<Self>) {
         let ts: DateTime<Utc> = SystemTime::now().into();
         let server_response = msg.raw.parse::<ServerResponse>().unwrap();
-        let server_response_containter = ServerResponseContainer {
-            ts,
-            raw_message: msg.raw.to_owned(),
-            server_response,
-            receiver_ts: None,
-            bearing: None,
-            distance: None,
-            normalized_signal_quality: None,
-            plausibility: None,
-        };
+        let server_response_containter =
+            ServerResponseContainer::new(server_response, ts, msg.raw.to_owned());
 
         match self.recipient.do_send(server_response_containter) {
             Ok(_) => {}
@@ -59,16 +51,8 @@ impl Handler<OGNMessageWithTimestamp> for ParserActor {
 
     fn handle(&mut self, msg: OGNMessageWithTimestamp, _: &mut Context<Self>) {
         let server_response = msg.raw.parse::<ServerResponse>().unwrap();
-        let server_response_containter = ServerResponseContainer {
-            ts: msg.ts,
-            raw_message: msg.raw.to_owned(),
-            server_response,
-            receiver_ts: None,
-            bearing: None,
-            distance: None,
-            normalized_signal_quality: None,
-            plausibility: None,
-        };
+        let server_response_containter =
+            ServerResponseContainer::new(server_response, msg.ts, msg.raw.to_owned());
 
         match self.recipient.do_send(server_response_containter) {
             Ok(_) => {}
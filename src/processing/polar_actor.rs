@@ -0,0 +1,196 @@
+use std::{collections::HashMap, time::Duration};
+
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use ogn_parser::{AprsData, ServerResponse};
+
+use crate::{
+    containers::{
+        containers::Container,
+        polar_container::{PolarContainer, PolarSector},
+        units::Units,
+    },
+    messages::server_response_container::ServerResponseContainer,
+};
+
+const SECTOR_SIZE: f64 = 10.0; // [°] of bearing
+const ALTITUDE_BAND: i32 = 2000; // [ft] relative to the receiver
+const RANGE_BIN: f64 = 1000.0; // [m] resolution of the range histogram
+const MAX_RANGE: f64 = 500_000.0; // [m] larger ranges are counted as this range (and keep the histogram small)
+const PERCENTILE: f64 = 0.9; // range which is reached by 90% of the positions
+
+const POLAR_INTERVAL: u64 = 10 * 60; // [s] the polar diagrams are sent periodically
+const POLAR_LIFETIME: i64 = 24 * 60 * 60; // [s] sectors without positions for this time are removed
+
+type Sector = (u16, i32);
+
+// histogram of the ranges of one sector and altitude band
+#[derive(Debug, Default)]
+pub struct RangeHistogram {
+    pub bins: Vec<u32>,
+    pub message_count: u32,
+    pub max_range: f64,
+    pub last_ts: Option<DateTime<Utc>>,
+}
+
+impl RangeHistogram {
+    fn add(&mut self, ts: DateTime<Utc>, range: f64) {
+        let range = range.min(MAX_RANGE);
+        let bin = (range / RANGE_BIN) as usize;
+        if bin >= self.bins.len() {
+            self.bins.resize(bin + 1, 0);
+        }
+        self.bins[bin] += 1;
+        self.message_count += 1;
+        self.max_range = self.max_range.max(range);
+        self.last_ts = self.last_ts.max(Some(ts));
+    }
+
+    // upper bound of the bin which contains the given percentile
+    fn percentile(&self, percentile: f64) -> f64 {
+        let limit = (self.message_count as f64 * percentile).ceil() as u32;
+        let mut count = 0;
+        for (bin, bin_count) in self.bins.iter().enumerate() {
+            count += bin_count;
+            if count >= limit {
+                return ((bin + 1) as f64 * RANGE_BIN).min(self.max_range);
+            }
+        }
+        self.max_range
+    }
+}
+
+pub struct PolarActor {
+    pub recipient: Recipient<ServerResponseContainer>,
+    pub polar_recipient: Recipient<Container>,
+
+    pub receivers: HashMap<String, HashMap<Sector, RangeHistogram>>,
+    pub last_ts: Option<DateTime<Utc>>,
+}
+
+impl PolarActor {
+    pub fn new(
+        recipient: Recipient<ServerResponseContainer>,
+        polar_recipient: Recipient<Container>,
+    ) -> Self {
+        PolarActor {
+            recipient,
+            polar_recipient,
+
+            receivers: HashMap::new(),
+            last_ts: None,
+        }
+    }
+
+    fn update(&mut self, msg: &ServerResponseContainer) {
+        let ServerResponse::AprsPacket(packet) = &msg.server_response else {
+            return;
+        };
+        let AprsData::Position(position) = &packet.data else {
+            return;
+        };
+        // only aircraft with known geometry to the receiver
+        let (Some(receiver), Some(bearing), Some(distance), Some(altitude_difference)) = (
+            packet.via.last(),
+            msg.bearing,
+            msg.distance,
+            msg.altitude_difference,
+        ) else {
+            return;
+        };
+        if position.comment.id.is_none() {
+            return;
+        }
+
+        let ts = msg.receiver_ts.unwrap_or(msg.ts);
+        self.last_ts = self.last_ts.max(Some(ts));
+
+        let sector = (
+            (bearing.rem_euclid(360.0) / SECTOR_SIZE) as u16,
+            altitude_difference.div_euclid(ALTITUDE_BAND) * ALTITUDE_BAND,
+        );
+        self.receivers
+            .entry(receiver.to_string())
+            .or_default()
+            .entry(sector)
+            .or_default()
+            .add(ts, distance);
+    }
+
+    // remove old sectors and send the polar diagrams of all receivers
+    fn send_polars(&mut self) {
+        let Some(last_ts) = self.last_ts else {
+            return;
+        };
+
+        for histograms in self.receivers.values_mut() {
+            histograms.retain(|_, histogram| {
+                histogram.last_ts.is_some_and(|ts| {
+                    last_ts.signed_duration_since(ts).num_seconds() <= POLAR_LIFETIME
+                })
+            });
+        }
+        self.receivers
+            .retain(|_, histograms| !histograms.is_empty());
+
+        for (receiver, histograms) in &self.receivers {
+            let mut sectors = histograms
+                .iter()
+                .map(|((sector, altitude_band), histogram)| PolarSector {
+                    ts: last_ts,
+                    units: Units::Aviation,
+                    receiver: receiver.clone(),
+                    bearing: (*sector as f64 + 0.5) * SECTOR_SIZE,
+                    altitude_band: *altitude_band,
+                    message_count: histogram.message_count,
+                    max_range: histogram.max_range,
+                    percentile_range: histogram.percentile(PERCENTILE),
+                })
+                .collect::<Vec<_>>();
+            sectors.sort_by(|a, b| {
+                (a.altitude_band, a.bearing)
+                    .partial_cmp(&(b.altitude_band, b.bearing))
+                    .unwrap()
+            });
+
+            let container = PolarContainer {
+                ts: last_ts,
+                units: Units::Aviation,
+                receiver: receiver.clone(),
+                sector_size: SECTOR_SIZE,
+                percentile: PERCENTILE,
+                sectors,
+            };
+            if let Err(err) = self.polar_recipient.do_send(Container::Polar(container)) {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
+
+impl Actor for PolarActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("PolarActor started");
+        ctx.run_interval(Duration::from_secs(POLAR_INTERVAL), |act, _ctx| {
+            act.send_polars();
+        });
+    }
+}
+
+impl Handler<ServerResponseContainer> for PolarActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: ServerResponseContainer, _: &mut Self::Context) {
+        self.update(&msg);
+
+        // Forward the message to the next actor in the chain
+        match self.recipient.do_send(msg) {
+            Ok(_) => (),
+            Err(err) => {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
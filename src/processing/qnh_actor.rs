@@ -0,0 +1,219 @@
+use std::{collections::HashMap, time::Duration};
+
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use ogn_parser::{AprsData, ServerResponse};
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::{
+    containers::{containers::Container, qnh_container::QnhContainer},
+    messages::server_response_container::{ReceiverGeometry, ServerResponseContainer},
+};
+
+// QNH samples are collected per region
+const REGION_SIZE: f64 = 0.5; // [°]
+
+// international standard atmosphere (troposphere)
+const STANDARD_PRESSURE: f64 = 1013.25; // [hPa]
+const ISA_HEIGHT: f64 = 145_366.45; // [ft] 44330.8m
+const ISA_EXPONENT: f64 = 5.255_88;
+
+// plausible QNH values [hPa]
+const MIN_QNH: f64 = 940.0;
+const MAX_QNH: f64 = 1060.0;
+
+const QNH_INTERVAL: u64 = 60; // [s] the regional QNH is sent periodically
+const QNH_LIFETIME: i64 = 30 * 60; // [s] samples older than this are not used anymore
+const TIMEOUT: i64 = 30 * 60; // [s] senders not seen for this time are removed
+
+type Region = (i32, i32);
+
+// static pressure [hPa] at the given altitude [ft] above the reference pressure level [hPa]
+fn pressure(altitude: f64, reference: f64) -> f64 {
+    reference * (1.0 - altitude / ISA_HEIGHT).powf(ISA_EXPONENT)
+}
+
+// altitude [ft] of the given static pressure [hPa] above the reference pressure level [hPa]
+fn altitude(pressure: f64, reference: f64) -> f64 {
+    ISA_HEIGHT * (1.0 - (pressure / reference).powf(1.0 / ISA_EXPONENT))
+}
+
+pub struct QnhActor {
+    pub recipient: Recipient<ServerResponseContainer>,
+    pub qnh_recipient: Recipient<Container>,
+
+    pub regions: HashMap<Region, Vec<(DateTime<Utc>, f64)>>,
+    pub senders: HashMap<String, DateTime<Utc>>,
+    pub last_ts: Option<DateTime<Utc>>,
+}
+
+impl QnhActor {
+    pub fn new(
+        recipient: Recipient<ServerResponseContainer>,
+        qnh_recipient: Recipient<Container>,
+    ) -> Self {
+        QnhActor {
+            recipient,
+            qnh_recipient,
+
+            regions: HashMap::new(),
+            senders: HashMap::new(),
+            last_ts: None,
+        }
+    }
+
+    fn region(latitude: f64, longitude: f64) -> Region {
+        (
+            (latitude / REGION_SIZE).floor() as i32,
+            (longitude / REGION_SIZE).floor() as i32,
+        )
+    }
+
+    // median of the recent samples of a region
+    fn qnh(&self, region: &Region) -> Option<f64> {
+        let mut samples = self
+            .regions
+            .get(region)?
+            .iter()
+            .map(|(_, qnh)| *qnh)
+            .collect::<Vec<_>>();
+        if samples.is_empty() {
+            return None;
+        }
+
+        samples.sort_by(f64::total_cmp);
+        Some(samples[samples.len() / 2])
+    }
+
+    fn update(&mut self, msg: &mut ServerResponseContainer) {
+        let ts = msg.receiver_ts.unwrap_or(msg.ts);
+        let ServerResponse::AprsPacket(packet) = &mut msg.server_response else {
+            return;
+        };
+        let AprsData::Position(position) = &mut packet.data else {
+            return;
+        };
+        let Some(flight_level) = position
+            .comment
+            .flight_level
+            .and_then(|flight_level| flight_level.to_f64())
+        else {
+            return;
+        };
+
+        let pressure_altitude = flight_level * 100.0;
+        let region = Self::region(*position.latitude, *position.longitude);
+        msg.pressure_altitude = Some(pressure_altitude.max(0.0).round() as u32);
+
+        // aircraft without GPS altitude send altitude 0 (or none at all)
+        match position.comment.altitude.filter(|altitude| *altitude > 0) {
+            Some(altitude) => {
+                // copies of the same position from other receivers are used only once
+                let sender = packet.from.to_string();
+                if self
+                    .senders
+                    .get(&sender)
+                    .is_some_and(|last_ts| ts <= *last_ts)
+                {
+                    return;
+                }
+                self.senders.insert(sender, ts);
+
+                let qnh = pressure(pressure_altitude, STANDARD_PRESSURE)
+                    / (1.0 - altitude as f64 / ISA_HEIGHT).powf(ISA_EXPONENT);
+                if (MIN_QNH..=MAX_QNH).contains(&qnh) {
+                    self.last_ts = self.last_ts.max(Some(ts));
+                    self.regions.entry(region).or_default().push((ts, qnh));
+                }
+            }
+            None => {
+                if let Some(qnh) = self.qnh(&region) {
+                    let altitude = altitude(pressure(pressure_altitude, STANDARD_PRESSURE), qnh);
+                    let altitude = altitude.max(0.0).round() as u32;
+                    position.comment.altitude = Some(altitude);
+                    msg.qnh = Some(qnh);
+                    msg.altitude_estimated = Some(true);
+
+                    // the validation didn't know the altitude, so the geometry to the receiver is completed here
+                    if let Some(distance) = msg.distance {
+                        let geometry = ReceiverGeometry::new(
+                            distance,
+                            Some(altitude),
+                            msg.receiver_altitude,
+                            position
+                                .comment
+                                .signal_quality
+                                .and_then(|signal_quality| signal_quality.to_f64()),
+                        );
+                        msg.slant_range = geometry.slant_range;
+                        msg.elevation_angle = geometry.elevation_angle;
+                        msg.altitude_difference = geometry.altitude_difference;
+                        msg.normalized_signal_quality = geometry.normalized_signal_quality;
+                    }
+                }
+            }
+        }
+    }
+
+    // send the QNH of all regions with recent samples, remove old samples and senders
+    fn send_qnh(&mut self) {
+        let Some(last_ts) = self.last_ts else {
+            return;
+        };
+
+        self.regions.retain(|_, samples| {
+            samples
+                .retain(|(ts, _)| last_ts.signed_duration_since(*ts).num_seconds() <= QNH_LIFETIME);
+            !samples.is_empty()
+        });
+        self.senders
+            .retain(|_, ts| last_ts.signed_duration_since(*ts).num_seconds() <= TIMEOUT);
+
+        for (region, samples) in &self.regions {
+            let Some(qnh) = self.qnh(region) else {
+                continue;
+            };
+
+            let (latitude, longitude) = region;
+            let container = QnhContainer {
+                ts: last_ts,
+                location: (
+                    (*longitude as f64 + 0.5) * REGION_SIZE,
+                    (*latitude as f64 + 0.5) * REGION_SIZE,
+                ),
+                qnh,
+                sample_count: samples.len() as u32,
+            };
+            if let Err(err) = self.qnh_recipient.do_send(Container::Qnh(container)) {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
+
+impl Actor for QnhActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("QnhActor started");
+        ctx.run_interval(Duration::from_secs(QNH_INTERVAL), |act, _ctx| {
+            act.send_qnh();
+        });
+    }
+}
+
+impl Handler<ServerResponseContainer> for QnhActor {
+    type Result = ();
+
+    fn handle(&mut self, mut msg: ServerResponseContainer, _: &mut Self::Context) {
+        self.update(&mut msg);
+
+        // Forward the message to the next actor in the chain
+        match self.recipient.do_send(msg) {
+            Ok(_) => (),
+            Err(err) => {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use std::{collections::HashMap, time::Duration};
+
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use ogn_parser::{AprsData, ServerResponse};
+
+use crate::{
+    containers::{
+        containers::Container,
+        fused_position_container::FusedPositionContainer,
+        protocol::Protocol,
+        sensitivity_container::{SensitivityContainer, SensitivityPair, SensitivityRank},
+    },
+    messages::server_response_container::ServerResponseContainer,
+};
+
+// receivers are ranked per region
+const REGION_SIZE: f64 = 1.0; // [°]
+
+// the score is the mean of the first comparisons, then a moving average
+const MIN_ALPHA: f64 = 0.01;
+
+const REPORT_INTERVAL: u64 = 10 * 60; // [s] the ranking is sent periodically
+const LIFETIME: i64 = 24 * 60 * 60; // [s] receivers and pairs without comparisons (or beacons) for this time are removed
+
+type Region = (i32, i32);
+
+#[derive(Debug, Default)]
+pub struct Score {
+    pub value: f64,
+    pub count: u32,
+    pub last_ts: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default)]
+pub struct PairStatistics {
+    pub count: u32,
+    pub sum_difference: f64,
+    pub last_ts: Option<DateTime<Utc>>,
+}
+
+// compares the normalized signal quality of receivers which received the same transmission (fused positions)
+pub struct SensitivityActor {
+    pub recipient: Recipient<ServerResponseContainer>,
+    pub container_recipient: Recipient<Container>,
+
+    pub receiver_regions: HashMap<String, (Region, DateTime<Utc>)>,
+    pub scores: HashMap<String, Score>,
+    pub pairs: HashMap<(String, String), PairStatistics>,
+    pub last_ts: Option<DateTime<Utc>>,
+}
+
+impl SensitivityActor {
+    pub fn new(
+        recipient: Recipient<ServerResponseContainer>,
+        container_recipient: Recipient<Container>,
+    ) -> Self {
+        SensitivityActor {
+            recipient,
+            container_recipient,
+
+            receiver_regions: HashMap::new(),
+            scores: HashMap::new(),
+            pairs: HashMap::new(),
+            last_ts: None,
+        }
+    }
+
+    fn region(latitude: f64, longitude: f64) -> Region {
+        (
+            (latitude / REGION_SIZE).floor() as i32,
+            (longitude / REGION_SIZE).floor() as i32,
+        )
+    }
+
+    fn update(&mut self, fused_position: &FusedPositionContainer) {
+        let qualities = fused_position
+            .receivers
+            .iter()
+            .filter_map(|receiver| {
+                receiver
+                    .receiver
+                    .as_ref()
+                    .zip(receiver.normalized_quality)
+                    .map(|(name, quality)| (name.to_string(), quality))
+            })
+            .collect::<Vec<_>>();
+        if qualities.len() < 2 {
+            return;
+        }
+        let ts = fused_position.ts;
+        self.last_ts = self.last_ts.max(Some(ts));
+
+        let mean =
+            qualities.iter().map(|(_, quality)| quality).sum::<f64>() / qualities.len() as f64;
+        for (receiver, quality) in &qualities {
+            let score = self.scores.entry(receiver.clone()).or_default();
+            score.count += 1;
+            let alpha = (1.0 / score.count as f64).max(MIN_ALPHA);
+            score.value += alpha * (quality - mean - score.value);
+            score.last_ts = score.last_ts.max(Some(ts));
+        }
+
+        for (index, (receiver_a, quality_a)) in qualities.iter().enumerate() {
+            for (receiver_b, quality_b) in &qualities[index + 1..] {
+                let (key, difference) = if receiver_a < receiver_b {
+                    (
+                        (receiver_a.clone(), receiver_b.clone()),
+                        quality_a - quality_b,
+                    )
+                } else {
+                    (
+                        (receiver_b.clone(), receiver_a.clone()),
+                        quality_b - quality_a,
+                    )
+                };
+                let pair = self.pairs.entry(key).or_default();
+                pair.count += 1;
+                pair.sum_difference += difference;
+                pair.last_ts = pair.last_ts.max(Some(ts));
+            }
+        }
+    }
+
+    // remove receivers and pairs not seen for a long time
+    fn remove_outdated(&mut self, last_ts: DateTime<Utc>) {
+        let is_recent = |ts: Option<DateTime<Utc>>| {
+            ts.is_some_and(|ts| last_ts.signed_duration_since(ts).num_seconds() <= LIFETIME)
+        };
+        self.scores.retain(|_, score| is_recent(score.last_ts));
+        self.pairs.retain(|_, pair| is_recent(pair.last_ts));
+        self.receiver_regions
+            .retain(|_, (_, ts)| is_recent(Some(*ts)));
+    }
+
+    // rank the receivers with known position per region
+    fn send_report(&mut self) {
+        let Some(last_ts) = self.last_ts else {
+            return;
+        };
+        self.remove_outdated(last_ts);
+
+        let mut regions: HashMap<Region, Vec<(&String, &Score)>> = HashMap::new();
+        for (receiver, score) in &self.scores {
+            if let Some((region, _)) = self.receiver_regions.get(receiver) {
+                regions.entry(*region).or_default().push((receiver, score));
+            }
+        }
+
+        for ((latitude, longitude), mut scores) in regions {
+            let location = (
+                (longitude as f64 + 0.5) * REGION_SIZE,
+                (latitude as f64 + 0.5) * REGION_SIZE,
+            );
+            scores.sort_by(|(_, a), (_, b)| b.value.total_cmp(&a.value));
+
+            let ranks = scores
+                .iter()
+                .enumerate()
+                .map(|(index, (receiver, score))| SensitivityRank {
+                    ts: last_ts,
+                    location,
+                    rank: index as u32 + 1,
+                    receiver: receiver.to_string(),
+                    score: score.value,
+                    comparison_count: score.count,
+                })
+                .collect::<Vec<_>>();
+            let pairs = self
+                .pairs
+                .iter()
+                .filter(|((receiver_a, receiver_b), _)| {
+                    [receiver_a, receiver_b].iter().all(|receiver| {
+                        self.receiver_regions
+                            .get(*receiver)
+                            .is_some_and(|(region, _)| *region == (latitude, longitude))
+                    })
+                })
+                .map(|((receiver_a, receiver_b), pair)| SensitivityPair {
+                    receiver_a: receiver_a.clone(),
+                    receiver_b: receiver_b.clone(),
+                    count: pair.count,
+                    mean_difference: pair.sum_difference / pair.count as f64,
+                })
+                .collect::<Vec<_>>();
+
+            let container = SensitivityContainer {
+                ts: last_ts,
+                location,
+                ranks,
+                pairs,
+            };
+            if let Err(err) = self
+                .container_recipient
+                .do_send(Container::Sensitivity(container))
+            {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
+
+impl Actor for SensitivityActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("SensitivityActor started");
+        ctx.run_interval(Duration::from_secs(REPORT_INTERVAL), |act, _ctx| {
+            act.send_report();
+        });
+    }
+}
+
+impl Handler<ServerResponseContainer> for SensitivityActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: ServerResponseContainer, _: &mut Self::Context) {
+        // the receiver beacons give the region of the receivers
+        if let ServerResponse::AprsPacket(packet) = &msg.server_response
+            && let AprsData::Position(position) = &packet.data
+            && Protocol::from_dst_call(&packet.to.to_string()) == Protocol::Receiver
+        {
+            self.receiver_regions.insert(
+                packet.from.to_string(),
+                (
+                    Self::region(*position.latitude, *position.longitude),
+                    msg.receiver_ts.unwrap_or(msg.ts),
+                ),
+            );
+        }
+
+        // Forward the message to the next actor in the chain
+        match self.recipient.do_send(msg) {
+            Ok(_) => (),
+            Err(err) => {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
+
+impl Handler<Container> for SensitivityActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: Container, _: &mut Self::Context) {
+        if let Container::FusedPosition(fused_position) = &msg {
+            self.update(fused_position);
+        }
+
+        // Forward the container to the distributor
+        match self.container_recipient.do_send(msg) {
+            Ok(_) => (),
+            Err(err) => {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::{
+    containers::{
+        address_type::AddressType,
+        aircraft_type::AircraftType,
+        containers::Container,
+        fused_position_container::FusedPositionContainer,
+        protocol::Protocol,
+        units::{self, Units},
+    },
+    utils::{http, icao},
+};
+
+const STATE_LIFETIME: i64 = 60 * 60; // [s] aircraft not seen for this time are removed
+const CLEANUP_INTERVAL: u64 = 60; // [s]
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackPoint {
+    pub ts: DateTime<Utc>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Metadata {
+    pub protocol: Protocol,
+    pub address: Option<u32>,
+    pub address_type_name: Option<AddressType>,
+    pub aircraft_type_name: Option<AircraftType>,
+    pub country: Option<&'static str>,
+    pub registration: Option<String>,
+}
+
+// current picture of an aircraft: last fused position, recent track and metadata
+#[derive(Debug, Clone, Serialize)]
+pub struct AircraftState {
+    pub src_call: String,
+    pub ts: DateTime<Utc>,
+    pub age: i64,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub metadata: Metadata,
+    pub position: FusedPositionContainer,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<Vec<TrackPoint>>,
+}
+
+#[derive(Debug, Default)]
+pub struct StateStore {
+    pub aircraft: HashMap<String, (FusedPositionContainer, VecDeque<TrackPoint>)>,
+    pub last_ts: Option<DateTime<Utc>>,
+}
+
+impl StateStore {
+    fn update(&mut self, position: FusedPositionContainer, track_length: usize) {
+        self.last_ts = self.last_ts.max(Some(position.ts));
+        let point = TrackPoint {
+            ts: position.ts,
+            latitude: position.location.1,
+            longitude: position.location.0,
+            altitude: position.altitude,
+        };
+
+        let (last, track) = self
+            .aircraft
+            .entry(position.src_call.to_string())
+            .or_insert_with(|| (position.clone(), VecDeque::new()));
+        if position.ts >= last.ts {
+            *last = position;
+        }
+        track.push_back(point);
+        while track.len() > track_length {
+            track.pop_front();
+        }
+    }
+
+    fn cleanup(&mut self) {
+        if let Some(last_ts) = self.last_ts {
+            self.aircraft.retain(|_, (last, _)| {
+                last_ts.signed_duration_since(last.ts).num_seconds() <= STATE_LIFETIME
+            });
+        }
+    }
+}
+
+// the age is relative to the latest position in the store
+fn age(last_ts: Option<DateTime<Utc>>, ts: DateTime<Utc>) -> i64 {
+    last_ts
+        .map(|last_ts| last_ts.signed_duration_since(ts).num_seconds())
+        .unwrap_or_default()
+}
+
+fn state(
+    last: FusedPositionContainer,
+    track: Option<VecDeque<TrackPoint>>,
+    last_ts: Option<DateTime<Utc>>,
+    units: Units,
+) -> AircraftState {
+    let is_icao = last.address_type_name == Some(AddressType::Icao);
+    let track = track.map(|track| {
+        track
+            .into_iter()
+            .map(|mut point| {
+                if units == Units::Si {
+                    point.altitude = point.altitude.map(units::feet_to_metres);
+                }
+                point
+            })
+            .collect()
+    });
+    AircraftState {
+        src_call: last.src_call.to_string(),
+        ts: last.ts,
+        age: age(last_ts, last.ts),
+        latitude: last.location.1,
+        longitude: last.location.0,
+        metadata: Metadata {
+            protocol: last.protocol,
+            address: last.address,
+            address_type_name: last.address_type_name,
+            aircraft_type_name: last.aircraft_type_name,
+            country: last.address.filter(|_| is_icao).and_then(icao::country),
+            registration: last.address.filter(|_| is_icao).and_then(icao::n_number),
+        },
+        position: match units {
+            Units::Aviation => last,
+            Units::Si => last.into_si(),
+        },
+        track,
+    }
+}
+
+// GET /aircraft[?bbox=<WEST>,<SOUTH>,<EAST>,<NORTH>][&max_age=<SECONDS>][&track=true]
+// GET /aircraft/<SRC_CALL> (with track)
+fn handle_request(
+    store: &Mutex<StateStore>,
+    units: Units,
+    request: &http::Request,
+) -> (&'static str, String) {
+    if request.method() != "GET" {
+        return (
+            "405 Method Not Allowed",
+            r#"{"error":"method not allowed"}"#.to_string(),
+        );
+    }
+
+    let query = http::query(request);
+    if request.uri().path() == "/aircraft" {
+        let bbox = match query.get("bbox").map(|bbox| http::parse_bbox(bbox)) {
+            Some(Err(err)) => {
+                return (
+                    "400 Bad Request",
+                    serde_json::json!({ "error": err }).to_string(),
+                );
+            }
+            Some(Ok(bbox)) => Some(bbox),
+            None => None,
+        };
+        let max_age = match query.get("max_age").map(|age| age.parse::<i64>()) {
+            Some(Err(err)) => {
+                return (
+                    "400 Bad Request",
+                    serde_json::json!({ "error": format!("invalid max_age: {err}") }).to_string(),
+                );
+            }
+            Some(Ok(max_age)) => Some(max_age),
+            None => None,
+        };
+        let with_track = query.get("track").is_some_and(|track| track == "true");
+
+        // only the matching entries are copied under the lock, the states are built without it
+        let (last_ts, entries) = {
+            let store = store.lock().unwrap();
+            let entries = store
+                .aircraft
+                .values()
+                .filter(|(last, _)| {
+                    bbox.is_none_or(|bbox| http::is_in_bbox(bbox, last.location.1, last.location.0))
+                })
+                .filter(|(last, _)| {
+                    max_age.is_none_or(|max_age| age(store.last_ts, last.ts) <= max_age)
+                })
+                .map(|(last, track)| (last.clone(), with_track.then(|| track.clone())))
+                .collect::<Vec<_>>();
+            (store.last_ts, entries)
+        };
+        let states = entries
+            .into_iter()
+            .map(|(last, track)| state(last, track, last_ts, units))
+            .collect::<Vec<_>>();
+        ("200 OK", serde_json::to_string(&states).unwrap())
+    } else if let Some(src_call) = request.uri().path().strip_prefix("/aircraft/") {
+        let (last_ts, entry) = {
+            let store = store.lock().unwrap();
+            (store.last_ts, store.aircraft.get(src_call).cloned())
+        };
+        match entry {
+            Some((last, track)) => (
+                "200 OK",
+                serde_json::to_string(&state(last, Some(track), last_ts, units)).unwrap(),
+            ),
+            None => (
+                "404 Not Found",
+                r#"{"error":"unknown aircraft"}"#.to_string(),
+            ),
+        }
+    } else {
+        ("404 Not Found", r#"{"error":"not found"}"#.to_string())
+    }
+}
+
+fn respond(mut stream: TcpStream, store: &Mutex<StateStore>, units: Units) {
+    let Some(request) = http::read_request(&stream) else {
+        return;
+    };
+    let (status, body) = handle_request(store, units, &request);
+    let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+    if let Err(err) = http::write_response(&mut stream, status, "application/json", &body) {
+        error!("Error writing API response: {err}");
+    }
+}
+
+// one thread per connection
+fn serve(listener: TcpListener, store: Arc<Mutex<StateStore>>, units: Units) {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let store = store.clone();
+                thread::spawn(move || respond(stream, &store, units));
+            }
+            Err(err) => error!("Error accepting API connection: {err}"),
+        }
+    }
+}
+
+// keeps the current picture of all aircraft (from the fused positions) and serves it as HTTP JSON API
+pub struct StateActor {
+    pub container_recipient: Recipient<Container>,
+    pub address: String,
+    pub track_length: usize,
+    pub units: Units,
+
+    pub store: Arc<Mutex<StateStore>>,
+}
+
+impl StateActor {
+    pub fn new(
+        container_recipient: Recipient<Container>,
+        address: &str,
+        track_length: usize,
+        units: Units,
+    ) -> Self {
+        StateActor {
+            container_recipient,
+            address: address.to_string(),
+            track_length,
+            units,
+
+            store: Arc::new(Mutex::new(StateStore::default())),
+        }
+    }
+}
+
+impl Actor for StateActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("StateActor started");
+        match TcpListener::bind(&self.address) {
+            Ok(listener) => {
+                info!("API listening on http://{}", self.address);
+                let store = self.store.clone();
+                let units = self.units;
+                thread::spawn(move || serve(listener, store, units));
+            }
+            Err(err) => error!("Error binding API to '{}': {err}", self.address),
+        }
+        ctx.run_interval(Duration::from_secs(CLEANUP_INTERVAL), |act, _ctx| {
+            act.store.lock().unwrap().cleanup();
+        });
+    }
+}
+
+impl Handler<Container> for StateActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: Container, _: &mut Self::Context) {
+        // receivers are no aircraft
+        if let Container::FusedPosition(fused_position) = &msg
+            && fused_position.protocol != Protocol::Receiver
+        {
+            self.store
+                .lock()
+                .unwrap()
+                .update(fused_position.clone(), self.track_length);
+        }
+
+        // Forward the container to the distributor
+        match self.container_recipient.do_send(msg) {
+            Ok(_) => (),
+            Err(err) => {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use ogn_parser::{AprsData, Callsign, ServerResponse};
+
+use crate::{
+    containers::{
+        containers::Container,
+        thermal_container::{ThermalContainer, ThermalHotspot, ThermalMapContainer},
+        units::Units,
+    },
+    messages::{circling_event::CirclingEvent, server_response_container::ServerResponseContainer},
+    utils::{circling::Circling, geo},
+};
+
+// thresholds for a thermal
+const MIN_THERMAL_TIME: i64 = 60; // [s] of circling
+const MIN_CLIMB_RATE: f64 = 100.0; // [fpm] ~0.5m/s
+
+// thermals closer than this are merged into one hotspot
+const HOTSPOT_DISTANCE: f64 = 1000.0; // [m]
+
+// the thermal map is sent periodically, hotspots lose half of their strength per half life
+const MAP_INTERVAL: u64 = 60; // [s]
+const HALF_LIFE: f64 = 20.0 * 60.0; // [s]
+const MAP_LIFETIME: i64 = 60 * 60; // [s] hotspots older than this are removed
+
+pub struct ThermalActor {
+    pub recipient: Recipient<ServerResponseContainer>,
+    pub thermal_recipient: Recipient<Container>,
+
+    pub hotspots: Vec<ThermalHotspot>,
+    pub last_ts: Option<DateTime<Utc>>,
+}
+
+impl ThermalActor {
+    pub fn new(
+        recipient: Recipient<ServerResponseContainer>,
+        thermal_recipient: Recipient<Container>,
+    ) -> Self {
+        ThermalActor {
+            recipient,
+            thermal_recipient,
+
+            hotspots: vec![],
+            last_ts: None,
+        }
+    }
+
+    fn send(&self, container: Container) {
+        if let Err(err) = self.thermal_recipient.do_send(container) {
+            error!("Error sending message to recipient: {err}");
+        }
+    }
+
+    fn to_thermal(src_call: &Callsign, circling: &Circling) -> Option<ThermalContainer> {
+        let duration = circling.duration();
+        if duration < MIN_THERMAL_TIME {
+            return None;
+        }
+
+        // the net climb of the whole phase, base and top may be reached in any order
+        let climb_rate = circling.altitude_gain() as f64 / duration as f64 * 60.0;
+        if climb_rate < MIN_CLIMB_RATE {
+            return None;
+        }
+
+        let (latitude, longitude) = circling.centre();
+        Some(ThermalContainer {
+            units: Units::Aviation,
+
+            src_call: src_call.clone(),
+            start_ts: circling.start_ts(),
+            end_ts: circling.end_ts(),
+            duration,
+            location: (longitude, latitude),
+            base_altitude: circling.base_altitude(),
+            top_altitude: circling.top_altitude(),
+            climb_rate,
+        })
+    }
+
+    fn add_hotspot(&mut self, thermal: &ThermalContainer) {
+        let (longitude, latitude) = thermal.location;
+        let hotspot = self.hotspots.iter_mut().find(|hotspot| {
+            geo::distance(hotspot.latitude, hotspot.longitude, latitude, longitude)
+                < HOTSPOT_DISTANCE
+        });
+
+        match hotspot {
+            Some(hotspot) => {
+                let count = hotspot.count as f64;
+                hotspot.latitude = (hotspot.latitude * count + latitude) / (count + 1.0);
+                hotspot.longitude = (hotspot.longitude * count + longitude) / (count + 1.0);
+                hotspot.climb_rate =
+                    (hotspot.climb_rate * count + thermal.climb_rate) / (count + 1.0);
+                hotspot.top_altitude = hotspot.top_altitude.max(thermal.top_altitude);
+                hotspot.count += 1;
+                hotspot.last_ts = hotspot.last_ts.max(thermal.end_ts);
+            }
+            None => self.hotspots.push(ThermalHotspot {
+                latitude,
+                longitude,
+                top_altitude: thermal.top_altitude,
+                climb_rate: thermal.climb_rate,
+                count: 1,
+                last_ts: thermal.end_ts,
+                strength: thermal.climb_rate,
+            }),
+        }
+    }
+
+    // decay and send the thermal map, remove old hotspots
+    fn send_map(&mut self) {
+        let Some(last_ts) = self.last_ts else {
+            return;
+        };
+
+        self.hotspots.retain_mut(|hotspot| {
+            let age = last_ts.signed_duration_since(hotspot.last_ts).num_seconds();
+            hotspot.strength = hotspot.climb_rate * 0.5f64.powf(age.max(0) as f64 / HALF_LIFE);
+            age <= MAP_LIFETIME
+        });
+
+        self.send(Container::ThermalMap(ThermalMapContainer {
+            ts: last_ts,
+            units: Units::Aviation,
+            hotspots: self.hotspots.clone(),
+        }));
+    }
+}
+
+impl Actor for ThermalActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("ThermalActor started");
+        ctx.run_interval(Duration::from_secs(MAP_INTERVAL), |act, _ctx| {
+            act.send_map();
+        });
+    }
+}
+
+impl Handler<ServerResponseContainer> for ThermalActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: ServerResponseContainer, _: &mut Self::Context) {
+        // the data time for the decay of the thermal map
+        if let ServerResponse::AprsPacket(packet) = &msg.server_response
+            && let AprsData::Position(_) = &packet.data
+        {
+            self.last_ts = self.last_ts.max(Some(msg.receiver_ts.unwrap_or(msg.ts)));
+        }
+
+        // Forward the message to the next actor in the chain
+        match self.recipient.do_send(msg) {
+            Ok(_) => (),
+            Err(err) => {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
+
+impl Handler<CirclingEvent> for ThermalActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: CirclingEvent, _: &mut Self::Context) {
+        if let CirclingEvent::Ended { src_call, circling } = msg
+            && let Some(thermal) = Self::to_thermal(&src_call, &circling)
+        {
+            self.add_hotspot(&thermal);
+            self.send(Container::Thermal(thermal));
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::{collections::HashMap, time::Duration};
+
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use ogn_parser::{AprsData, ServerResponse};
+
+use crate::{
+    containers::{containers::Container, protocol::Protocol, timing_container::TimingContainer},
+    messages::server_response_container::ServerResponseContainer,
+};
+
+const TIMING_INTERVAL: u64 = 60; // [s] the statistics are sent periodically
+const TIMING_WINDOW: i64 = 10 * 60; // [s] samples older than this are not used anymore
+const MIN_SAMPLES: usize = 10; // receivers with less samples are not rated
+const MAX_CLOCK_OFFSET: f64 = 2.0; // [s] receivers with a larger offset have a bad clock (e.g. NTP not working)
+
+// value at the given percentile of sorted values
+fn percentile(values: &[f64], percentile: f64) -> f64 {
+    let index = ((values.len() - 1) as f64 * percentile).round() as usize;
+    values[index]
+}
+
+pub struct TimingActor {
+    pub recipient: Recipient<ServerResponseContainer>,
+    pub timing_recipient: Recipient<Container>,
+
+    // (ingest ts, delay [s]) per receiver
+    pub receivers: HashMap<String, Vec<(DateTime<Utc>, f64)>>,
+    pub last_ts: Option<DateTime<Utc>>,
+}
+
+impl TimingActor {
+    pub fn new(
+        recipient: Recipient<ServerResponseContainer>,
+        timing_recipient: Recipient<Container>,
+    ) -> Self {
+        TimingActor {
+            recipient,
+            timing_recipient,
+
+            receivers: HashMap::new(),
+            last_ts: None,
+        }
+    }
+
+    fn update(&mut self, msg: &ServerResponseContainer) {
+        let ServerResponse::AprsPacket(packet) = &msg.server_response else {
+            return;
+        };
+        // only positions relayed by a receiver (receiver beacons come directly from the server)
+        if !matches!(packet.data, AprsData::Position(_))
+            || Protocol::from_dst_call(&packet.to.to_string()) == Protocol::Receiver
+        {
+            return;
+        }
+        let (Some(receiver), Some(receiver_ts)) = (packet.via.last(), msg.receiver_ts) else {
+            return;
+        };
+
+        let delay = msg.ts.signed_duration_since(receiver_ts).num_milliseconds() as f64 / 1000.0;
+        self.last_ts = self.last_ts.max(Some(msg.ts));
+        self.receivers
+            .entry(receiver.to_string())
+            .or_default()
+            .push((msg.ts, delay));
+    }
+
+    // remove old samples and send the statistics of all receivers
+    fn send_timings(&mut self) {
+        let Some(last_ts) = self.last_ts else {
+            return;
+        };
+
+        self.receivers.retain(|_, samples| {
+            samples.retain(|(ts, _)| {
+                last_ts.signed_duration_since(*ts).num_seconds() <= TIMING_WINDOW
+            });
+            !samples.is_empty()
+        });
+
+        let delays = self
+            .receivers
+            .iter()
+            .filter(|(_, samples)| samples.len() >= MIN_SAMPLES)
+            .map(|(receiver, samples)| {
+                let mut delays = samples.iter().map(|(_, delay)| *delay).collect::<Vec<_>>();
+                delays.sort_by(f64::total_cmp);
+                (receiver, delays)
+            })
+            .collect::<Vec<_>>();
+        if delays.is_empty() {
+            return;
+        }
+
+        // most receivers have a good clock, so the median of all receivers is the network latency
+        let mut medians = delays
+            .iter()
+            .map(|(_, delays)| percentile(delays, 0.5))
+            .collect::<Vec<_>>();
+        medians.sort_by(f64::total_cmp);
+        let network_delay = percentile(&medians, 0.5);
+
+        for (receiver, delays) in delays {
+            let median_delay = percentile(&delays, 0.5);
+            let clock_offset = median_delay - network_delay;
+            let timing = TimingContainer {
+                ts: last_ts,
+                receiver: receiver.clone(),
+                sample_count: delays.len() as u32,
+                min_delay: delays[0],
+                p10_delay: percentile(&delays, 0.1),
+                median_delay,
+                p90_delay: percentile(&delays, 0.9),
+                clock_offset,
+                bad_clock: clock_offset.abs() > MAX_CLOCK_OFFSET,
+            };
+            if let Err(err) = self.timing_recipient.do_send(Container::Timing(timing)) {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
+
+impl Actor for TimingActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("TimingActor started");
+        ctx.run_interval(Duration::from_secs(TIMING_INTERVAL), |act, _ctx| {
+            act.send_timings();
+        });
+    }
+}
+
+impl Handler<ServerResponseContainer> for TimingActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: ServerResponseContainer, _: &mut Self::Context) {
+        self.update(&msg);
+
+        // Forward the message to the next actor in the chain
+        match self.recipient.do_send(msg) {
+            Ok(_) => (),
+            Err(err) => {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
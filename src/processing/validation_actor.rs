@@ -1,11 +1,11 @@
-use std::{collections::HashMap, time::SystemTime};
+use std::collections::HashMap;
 
 use actix::prelude::*;
 use chrono::{DateTime, Utc};
 use ogn_parser::{AprsData, AprsPosition, ServerResponse};
 use rust_decimal::prelude::*;
 
-use crate::messages::server_response_container::ServerResponseContainer;
+use crate::messages::server_response_container::{ReceiverGeometry, ServerResponseContainer};
 
 pub struct ValidationActor {
     pub recipient: Recipient<ServerResponseContainer>,
@@ -50,15 +50,17 @@ impl Handler<ServerResponseContainer> for ValidationActor {
                 let sender_name = &packet.from.call;
                 let receiver_name = &packet.via.iter().last().unwrap().call;
                 if let AprsData::Position(position) = &packet.data {
-                    // calculate absolute timestamp based on the relative timestamp and a reference time (from the server or localhost)
+                    // calculate absolute timestamp based on the relative timestamp and a reference time (from the server or the ingest time)
                     let timestamp_actual = if let Some(position_timestamp) = &position.timestamp {
                         if use_server_timestamp {
                             self.last_server_timestamp.and_then(|reference| {
                                 position_timestamp.to_datetime(&reference).ok()
                             })
                         } else {
-                            let reference = DateTime::<Utc>::from(SystemTime::now());
-                            position_timestamp.to_datetime(&reference).ok()
+                            // the ingest time is "now" for the live stream and keeps logfiles reproducible
+                            position_timestamp
+                                .to_datetime(&server_response_containter.ts)
+                                .ok()
                         }
                     } else {
                         None
@@ -72,20 +74,26 @@ impl Handler<ServerResponseContainer> for ValidationActor {
                         let bearing = relation.bearing;
                         let distance = relation.distance;
 
-                        let normalized_signal_quality =
-                            position.comment.signal_quality.and_then(|signal_quality| {
-                                let signal_quality = signal_quality.to_f64().unwrap();
-                                if signal_quality > 0.0 {
-                                    Some(signal_quality + 20.0 * (distance / 10_000.0).log10())
-                                } else {
-                                    None
-                                }
-                            });
+                        // with both altitudes known we have the 3D geometry (slant range and elevation angle)
+                        let geometry = ReceiverGeometry::new(
+                            distance,
+                            position.comment.altitude,
+                            receiver.comment.altitude,
+                            position
+                                .comment
+                                .signal_quality
+                                .and_then(|signal_quality| signal_quality.to_f64()),
+                        );
 
                         server_response_containter.bearing = Some(bearing);
                         server_response_containter.distance = Some(distance);
+                        server_response_containter.receiver_altitude = receiver.comment.altitude;
+                        server_response_containter.slant_range = geometry.slant_range;
+                        server_response_containter.elevation_angle = geometry.elevation_angle;
+                        server_response_containter.altitude_difference =
+                            geometry.altitude_difference;
                         server_response_containter.normalized_signal_quality =
-                            normalized_signal_quality;
+                            geometry.normalized_signal_quality;
                     }
 
                     // calculate the plausibility of the message
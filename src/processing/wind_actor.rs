@@ -0,0 +1,290 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use ogn_parser::{AprsData, AprsPacket, AprsPosition, ServerResponse};
+
+use crate::{
+    containers::{
+        containers::Container,
+        units::{self, Units},
+        wind_container::WindContainer,
+    },
+    messages::{circling_event::CirclingEvent, server_response_container::ServerResponseContainer},
+    utils::{circling::Circle, geo},
+};
+
+// wind estimates are collected per region and altitude band
+const REGION_SIZE: f64 = 0.5; // [°]
+const ALTITUDE_BAND: u32 = 1000; // [ft]
+
+// circle drift: max. time between two circles [s] and max. plausible wind speed [m/s]
+const MAX_CIRCLE_INTERVAL: i64 = 120;
+const MAX_WIND_SPEED: f64 = 40.0;
+
+const WIND_INTERVAL: u64 = 60; // [s] the changed cells are sent periodically
+const WIND_LIFETIME: i64 = 30 * 60; // [s] estimates older than this are not used anymore
+const TIMEOUT: i64 = 30 * 60; // [s] sender not seen for this time is removed
+
+type Cell = (i32, i32, u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindSource {
+    Circling,
+    Station,
+}
+
+// wind vector [m/s] the air is moving to
+pub struct WindSample {
+    pub ts: DateTime<Utc>,
+    pub east: f64,
+    pub north: f64,
+    pub gust: Option<f64>,
+    pub source: WindSource,
+}
+
+pub struct WindActor {
+    pub recipient: Recipient<ServerResponseContainer>,
+    pub wind_recipient: Recipient<Container>,
+
+    // last time of each weather station and last circle of each circling aircraft
+    pub stations: HashMap<String, DateTime<Utc>>,
+    pub circles: HashMap<String, Circle>,
+    pub cells: HashMap<Cell, Vec<WindSample>>,
+    pub changed: HashSet<Cell>,
+    pub last_ts: Option<DateTime<Utc>>,
+}
+
+impl WindActor {
+    pub fn new(
+        recipient: Recipient<ServerResponseContainer>,
+        wind_recipient: Recipient<Container>,
+    ) -> Self {
+        WindActor {
+            recipient,
+            wind_recipient,
+
+            stations: HashMap::new(),
+            circles: HashMap::new(),
+            cells: HashMap::new(),
+            changed: HashSet::new(),
+            last_ts: None,
+        }
+    }
+
+    fn cell(latitude: f64, longitude: f64, altitude: u32) -> Cell {
+        (
+            (latitude / REGION_SIZE).floor() as i32,
+            (longitude / REGION_SIZE).floor() as i32,
+            altitude / ALTITUDE_BAND,
+        )
+    }
+
+    // weather station
+    fn update(&mut self, packet: &AprsPacket, position: &AprsPosition, ts: DateTime<Utc>) {
+        let comment = &position.comment;
+        let (Some(wind_direction), Some(wind_speed)) = (comment.wind_direction, comment.wind_speed)
+        else {
+            return;
+        };
+
+        // copies of the same position from other receivers
+        let src_call = packet.from.to_string();
+        if self
+            .stations
+            .get(&src_call)
+            .is_some_and(|last_ts| ts <= *last_ts)
+        {
+            return;
+        }
+        self.stations.insert(src_call, ts);
+
+        let speed = wind_speed as f64 * units::MPH;
+        let to = ((wind_direction as f64) + 180.0).to_radians();
+        let cell = Self::cell(
+            *position.latitude,
+            *position.longitude,
+            comment.altitude.unwrap_or_default(),
+        );
+        self.add_sample(
+            cell,
+            WindSample {
+                ts,
+                east: speed * to.sin(),
+                north: speed * to.cos(),
+                gust: comment.gust.map(|gust| gust as f64 * units::MPH),
+                source: WindSource::Station,
+            },
+        );
+    }
+
+    // the drift of two successive circles is the wind
+    fn add_circle(&mut self, src_call: &str, actual: Circle) {
+        let Some(previous) = self.circles.insert(src_call.to_string(), actual.clone()) else {
+            return;
+        };
+
+        let seconds = actual
+            .ts
+            .signed_duration_since(previous.ts)
+            .num_milliseconds() as f64
+            / 1000.0;
+        if seconds <= 0.0 || seconds > MAX_CIRCLE_INTERVAL as f64 {
+            return;
+        }
+
+        let (east, north) = geo::offset(
+            previous.latitude,
+            previous.longitude,
+            actual.latitude,
+            actual.longitude,
+        );
+        let (east, north) = (east / seconds, north / seconds);
+        if east.hypot(north) > MAX_WIND_SPEED {
+            return;
+        }
+
+        let cell = Self::cell(
+            (previous.latitude + actual.latitude) / 2.0,
+            (previous.longitude + actual.longitude) / 2.0,
+            (previous.altitude + actual.altitude) / 2,
+        );
+        self.add_sample(
+            cell,
+            WindSample {
+                ts: actual.ts,
+                east,
+                north,
+                gust: None,
+                source: WindSource::Circling,
+            },
+        );
+    }
+
+    fn add_sample(&mut self, cell: Cell, sample: WindSample) {
+        self.cells.entry(cell).or_default().push(sample);
+        self.changed.insert(cell);
+    }
+
+    // (vector) average of the recent samples of a cell
+    fn average(cell: Cell, samples: &[WindSample]) -> Option<WindContainer> {
+        let ts = samples.iter().map(|sample| sample.ts).max()?;
+        let count = samples.len() as f64;
+        let east = samples.iter().map(|sample| sample.east).sum::<f64>() / count;
+        let north = samples.iter().map(|sample| sample.north).sum::<f64>() / count;
+        let gust = samples
+            .iter()
+            .filter_map(|sample| sample.gust)
+            .reduce(f64::max);
+        let count_of = |source| {
+            samples
+                .iter()
+                .filter(|sample| sample.source == source)
+                .count() as u32
+        };
+
+        let (latitude, longitude, band) = cell;
+        Some(WindContainer {
+            ts,
+            units: Units::Aviation,
+            location: (
+                (longitude as f64 + 0.5) * REGION_SIZE,
+                (latitude as f64 + 0.5) * REGION_SIZE,
+            ),
+            altitude_band: band * ALTITUDE_BAND,
+            wind_direction: ((-east).atan2(-north).to_degrees().round() as i32).rem_euclid(360)
+                as u16,
+            wind_speed: units::mps_to_knots(east.hypot(north)),
+            gust: gust.map(units::mps_to_knots),
+            circling_count: count_of(WindSource::Circling),
+            station_count: count_of(WindSource::Station),
+        })
+    }
+
+    // send the average of all cells with new samples since the last interval
+    fn send_winds(&mut self) {
+        self.remove_outdated();
+
+        for cell in self.changed.drain() {
+            let Some(wind) = self
+                .cells
+                .get(&cell)
+                .and_then(|samples| Self::average(cell, samples))
+            else {
+                continue;
+            };
+            if let Err(err) = self.wind_recipient.do_send(Container::Wind(wind)) {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+
+    // remove old samples, stations and circles
+    fn remove_outdated(&mut self) {
+        let Some(last_ts) = self.last_ts else {
+            return;
+        };
+
+        self.cells.retain(|_, samples| {
+            samples.retain(|sample| {
+                last_ts.signed_duration_since(sample.ts).num_seconds() <= WIND_LIFETIME
+            });
+            !samples.is_empty()
+        });
+        self.stations
+            .retain(|_, ts| last_ts.signed_duration_since(*ts).num_seconds() <= TIMEOUT);
+        self.circles
+            .retain(|_, circle| last_ts.signed_duration_since(circle.ts).num_seconds() <= TIMEOUT);
+    }
+}
+
+impl Actor for WindActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("WindActor started");
+        ctx.run_interval(Duration::from_secs(WIND_INTERVAL), |act, _ctx| {
+            act.send_winds();
+        });
+    }
+}
+
+impl Handler<ServerResponseContainer> for WindActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: ServerResponseContainer, _: &mut Self::Context) {
+        if let ServerResponse::AprsPacket(packet) = &msg.server_response
+            && let AprsData::Position(position) = &packet.data
+        {
+            let ts = msg.receiver_ts.unwrap_or(msg.ts);
+            self.last_ts = self.last_ts.max(Some(ts));
+            self.update(packet, position, ts);
+        }
+
+        // Forward the message to the next actor in the chain
+        match self.recipient.do_send(msg) {
+            Ok(_) => (),
+            Err(err) => {
+                error!("Error sending message to recipient: {err}");
+            }
+        }
+    }
+}
+
+impl Handler<CirclingEvent> for WindActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: CirclingEvent, _: &mut Self::Context) {
+        match msg {
+            CirclingEvent::Circle { src_call, circle } => {
+                self.add_circle(&src_call.to_string(), circle)
+            }
+            CirclingEvent::Ended { src_call, .. } => {
+                self.circles.remove(&src_call.to_string());
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use std::{
+    io,
+    net::{Shutdown, TcpStream},
+    sync::{
+        Mutex,
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+    },
+    thread,
+};
+
+const QUEUE_SIZE: usize = 256; // messages waiting to be written to a client, a client with a full queue is dropped
+
+struct Client<M, F> {
+    stream: TcpStream,
+    sender: SyncSender<M>,
+    filter: F,
+}
+
+// sends messages to TCP clients: every client has a bounded queue, which is written by its own thread,
+// so a slow client doesn't block the sender or the other clients
+pub struct Broadcast<M, F = ()> {
+    name: &'static str,
+    clients: Mutex<Vec<Client<M, F>>>,
+}
+
+impl<M: Clone + Send + 'static, F> Broadcast<M, F> {
+    pub fn new(name: &'static str) -> Self {
+        Broadcast {
+            name,
+            clients: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clients.lock().unwrap().is_empty()
+    }
+
+    // the thread gets the queued messages of the client, the client is removed when the thread has finished
+    pub fn add(
+        &self,
+        stream: &TcpStream,
+        filter: F,
+        run: impl FnOnce(Receiver<M>) + Send + 'static,
+    ) -> io::Result<()> {
+        let (sender, receiver) = mpsc::sync_channel(QUEUE_SIZE);
+        self.clients.lock().unwrap().push(Client {
+            stream: stream.try_clone()?,
+            sender,
+            filter,
+        });
+        thread::spawn(move || run(receiver));
+        Ok(())
+    }
+
+    // a client with a full queue is disconnected
+    pub fn send(&self, message: M, matches: impl Fn(&F) -> bool) {
+        self.clients.lock().unwrap().retain(|client| {
+            if !matches(&client.filter) {
+                return true;
+            }
+            match client.sender.try_send(message.clone()) {
+                Ok(_) => true,
+                Err(TrySendError::Full(_)) => {
+                    info!("{} client is too slow, disconnected", self.name);
+                    let _ = client.stream.shutdown(Shutdown::Both);
+                    false
+                }
+                Err(TrySendError::Disconnected(_)) => false,
+            }
+        });
+    }
+}
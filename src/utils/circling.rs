@@ -0,0 +1,192 @@
+use chrono::{DateTime, Utc};
+
+// turn rate [rot] (1 rot = 3°/s) above which an aircraft is circling
+const CIRCLING_TURN_RATE: f64 = 1.5;
+
+// max. time without turning before circling ends [s]
+const CIRCLING_GAP: i64 = 15;
+
+// a single fix of a circling aircraft
+#[derive(Debug, Clone)]
+pub struct CirclingFix {
+    pub ts: DateTime<Utc>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude: u32,
+    pub course: Option<u16>,
+}
+
+// a full circle (360° of course change) of a circling phase, with its mean time and position
+#[derive(Debug, Clone)]
+pub struct Circle {
+    pub ts: DateTime<Utc>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude: u32,
+}
+
+// a finished (or ongoing) phase of circling
+#[derive(Debug, Clone)]
+pub struct Circling {
+    pub fixes: Vec<CirclingFix>,
+
+    // the circles are completed incrementally with every fix
+    circle_start: usize,
+    turned: i32, // [°] course change since the start of the unfinished circle
+}
+
+impl Circling {
+    fn new() -> Self {
+        Circling {
+            fixes: vec![],
+            circle_start: 0,
+            turned: 0,
+        }
+    }
+
+    pub fn start_ts(&self) -> DateTime<Utc> {
+        self.fixes[0].ts
+    }
+
+    pub fn end_ts(&self) -> DateTime<Utc> {
+        self.fixes[self.fixes.len() - 1].ts
+    }
+
+    pub fn duration(&self) -> i64 {
+        self.end_ts()
+            .signed_duration_since(self.start_ts())
+            .num_seconds()
+    }
+
+    // mean position (latitude, longitude)
+    pub fn centre(&self) -> (f64, f64) {
+        let count = self.fixes.len() as f64;
+        let latitude = self.fixes.iter().map(|fix| fix.latitude).sum::<f64>() / count;
+        let longitude = self.fixes.iter().map(|fix| fix.longitude).sum::<f64>() / count;
+        (latitude, longitude)
+    }
+
+    pub fn base_altitude(&self) -> u32 {
+        self.fixes
+            .iter()
+            .map(|fix| fix.altitude)
+            .min()
+            .unwrap_or_default()
+    }
+
+    pub fn top_altitude(&self) -> u32 {
+        self.fixes
+            .iter()
+            .map(|fix| fix.altitude)
+            .max()
+            .unwrap_or_default()
+    }
+
+    // [ft] from the first to the last fix, negative if the aircraft has sunk
+    pub fn altitude_gain(&self) -> i64 {
+        match (self.fixes.first(), self.fixes.last()) {
+            (Some(first), Some(last)) => last.altitude as i64 - first.altitude as i64,
+            _ => 0,
+        }
+    }
+
+    // add a fix, returns the circle (360° of course change since the last one) if it is completed by this fix
+    fn push(&mut self, fix: CirclingFix) -> Option<Circle> {
+        if let (Some(previous), Some(actual)) =
+            (self.fixes.last().and_then(|fix| fix.course), fix.course)
+        {
+            let delta = (actual as i32 - previous as i32).rem_euclid(360);
+            self.turned += delta.min(360 - delta);
+        }
+        self.fixes.push(fix);
+        if self.turned < 360 {
+            return None;
+        }
+
+        let end = self.fixes.len() - 1;
+        let fixes = &self.fixes[self.circle_start..=end];
+        let count = fixes.len() as f64;
+        let first_ts = fixes[0].ts;
+        let millis = fixes
+            .iter()
+            .map(|fix| fix.ts.signed_duration_since(first_ts).num_milliseconds())
+            .sum::<i64>()
+            / fixes.len() as i64;
+        let circle = Circle {
+            ts: first_ts + chrono::Duration::milliseconds(millis),
+            latitude: fixes.iter().map(|fix| fix.latitude).sum::<f64>() / count,
+            longitude: fixes.iter().map(|fix| fix.longitude).sum::<f64>() / count,
+            altitude: (fixes.iter().map(|fix| fix.altitude as f64).sum::<f64>() / count) as u32,
+        };
+        self.circle_start = end;
+        self.turned = 0;
+        Some(circle)
+    }
+}
+
+// result of a fix: a completed circle of the ongoing phase and/or the circling phase which has ended
+#[derive(Debug, Default)]
+pub struct CirclingUpdate {
+    pub circle: Option<Circle>,
+    pub ended: Option<Circling>,
+}
+
+// detects circling of a single aircraft by its turn rate (or course changes if the turn rate is missing)
+#[derive(Debug, Default)]
+pub struct CirclingDetector {
+    pub circling: Option<Circling>,
+    pub last_turn_ts: Option<DateTime<Utc>>,
+    pub last_course: Option<(DateTime<Utc>, u16)>,
+}
+
+impl CirclingDetector {
+    fn is_turning(&self, ts: DateTime<Utc>, course: Option<u16>, turn_rate: Option<f64>) -> bool {
+        if let Some(turn_rate) = turn_rate {
+            return turn_rate.abs() >= CIRCLING_TURN_RATE;
+        }
+
+        match (self.last_course, course) {
+            (Some((last_ts, last_course)), Some(course)) => {
+                let seconds = ts.signed_duration_since(last_ts).num_milliseconds() as f64 / 1000.0;
+                let delta = (course as i32 - last_course as i32).rem_euclid(360);
+                let delta = delta.min(360 - delta) as f64;
+                seconds > 0.0 && delta / seconds >= CIRCLING_TURN_RATE * 3.0
+            }
+            _ => false,
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        fix: CirclingFix,
+        course: Option<u16>,
+        turn_rate: Option<f64>,
+    ) -> CirclingUpdate {
+        let ts = fix.ts;
+        let is_turning = self.is_turning(ts, course, turn_rate);
+        self.last_course = course.map(|course| (ts, course));
+
+        if is_turning {
+            self.last_turn_ts = Some(ts);
+            CirclingUpdate {
+                circle: self.circling.get_or_insert_with(Circling::new).push(fix),
+                ended: None,
+            }
+        } else if self.last_turn_ts.is_some_and(|last_turn_ts| {
+            ts.signed_duration_since(last_turn_ts).num_seconds() > CIRCLING_GAP
+        }) {
+            CirclingUpdate {
+                circle: None,
+                ended: self.finish(),
+            }
+        } else {
+            CirclingUpdate::default()
+        }
+    }
+
+    // end an ongoing circling phase, e.g. when the aircraft is not seen anymore
+    pub fn finish(&mut self) -> Option<Circling> {
+        self.last_turn_ts = None;
+        self.circling.take()
+    }
+}
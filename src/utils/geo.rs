@@ -0,0 +1,32 @@
+// mean earth radius [m]
+pub const EARTH_RADIUS: f64 = 6_371_000.0;
+
+// great circle distance [m] between two points (latitude, longitude in degrees)
+pub fn distance(latitude1: f64, longitude1: f64, latitude2: f64, longitude2: f64) -> f64 {
+    let phi1 = latitude1.to_radians();
+    let phi2 = latitude2.to_radians();
+    let delta_phi = (latitude2 - latitude1).to_radians();
+    let delta_lambda = (longitude2 - longitude1).to_radians();
+
+    let a = (delta_phi / 2.0).sin().powi(2)
+        + phi1.cos() * phi2.cos() * (delta_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * a.sqrt().asin()
+}
+
+// offset [m] to the east and to the north from point 1 to point 2, good enough for short distances
+pub fn offset(latitude1: f64, longitude1: f64, latitude2: f64, longitude2: f64) -> (f64, f64) {
+    let phi = ((latitude1 + latitude2) / 2.0).to_radians();
+    let east = (longitude2 - longitude1).to_radians() * EARTH_RADIUS * phi.cos();
+    let north = (latitude2 - latitude1).to_radians() * EARTH_RADIUS;
+    (east, north)
+}
+
+// slant range [m] and elevation angle [°] of a target at the given ground distance [m] and height above the observer [m],
+// the elevation angle is corrected for the curvature of the earth
+pub fn slant(distance: f64, height: f64) -> (f64, f64) {
+    let slant_range = (distance.powi(2) + height.powi(2)).sqrt();
+    let elevation_angle = (height - distance.powi(2) / (2.0 * EARTH_RADIUS))
+        .atan2(distance)
+        .to_degrees();
+    (slant_range, elevation_angle)
+}
@@ -0,0 +1,156 @@
+use std::{
+    collections::HashMap,
+    io::{self, Read, Write},
+    net::TcpStream,
+    time::{Duration, Instant},
+};
+
+use tungstenite::http::{self, Version};
+
+pub type Request = http::Request<()>;
+
+const MAX_HEADERS: usize = 32;
+const MAX_REQUEST_SIZE: u64 = 16 * 1024; // [bytes] request line and headers
+const MAX_REQUEST_TIME: Duration = Duration::from_secs(5); // to read the whole request
+
+// reads from the stream until the deadline, so a slow client can't block the server
+struct DeadlineReader<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+}
+
+impl Read for DeadlineReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(remaining))?;
+        (&mut &*self.stream).read(buf)
+    }
+}
+
+fn to_request(parsed: &httparse::Request) -> Option<Request> {
+    let version = match parsed.version? {
+        0 => Version::HTTP_10,
+        _ => Version::HTTP_11,
+    };
+    parsed
+        .headers
+        .iter()
+        .fold(
+            Request::builder()
+                .method(parsed.method?)
+                .uri(parsed.path?)
+                .version(version),
+            |builder, header| builder.header(header.name, header.value),
+        )
+        .body(())
+        .ok()
+}
+
+// read the request line and the headers (max. 16kB within 5s), a body is not supported
+pub fn read_request(stream: &TcpStream) -> Option<Request> {
+    let mut reader = DeadlineReader {
+        stream,
+        deadline: Instant::now() + MAX_REQUEST_TIME,
+    }
+    .take(MAX_REQUEST_SIZE);
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 1024];
+    let request = loop {
+        match reader.read(&mut chunk) {
+            Ok(0) | Err(_) => break None,
+            Ok(count) => buffer.extend_from_slice(&chunk[..count]),
+        }
+        let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+        let mut parsed = httparse::Request::new(&mut headers);
+        match parsed.parse(&buffer) {
+            Ok(httparse::Status::Complete(_)) => break to_request(&parsed),
+            Ok(httparse::Status::Partial) => continue,
+            Err(_) => break None,
+        }
+    };
+
+    // the stream is used without timeout afterwards, e.g. by a WebSocket
+    stream.set_read_timeout(None).ok()?;
+    request
+}
+
+// decoded query parameters of the request
+pub fn query(request: &Request) -> HashMap<String, String> {
+    form_urlencoded::parse(request.uri().query().unwrap_or_default().as_bytes())
+        .into_owned()
+        .collect()
+}
+
+pub fn write_response(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes())
+}
+
+// bounding box "<WEST>,<SOUTH>,<EAST>,<NORTH>" in degrees
+pub fn parse_bbox(value: &str) -> Result<(f64, f64, f64, f64), String> {
+    let values = value
+        .split(',')
+        .map(|part| {
+            part.parse::<f64>()
+                .map_err(|err| format!("invalid bbox value '{part}': {err}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    match values[..] {
+        [west, south, east, north] => Ok((west, south, east, north)),
+        _ => Err(format!(
+            "expected bbox <WEST>,<SOUTH>,<EAST>,<NORTH>, got '{value}'"
+        )),
+    }
+}
+
+pub fn is_in_bbox(bbox: (f64, f64, f64, f64), latitude: f64, longitude: f64) -> bool {
+    let (west, south, east, north) = bbox;
+    (south..=north).contains(&latitude) && (west..=east).contains(&longitude)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::*;
+
+    #[test]
+    fn test_read_request() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        client
+            .write_all(b"GET /aircraft?bbox=5.5%2C47%2C15%2C55&track=true HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+        let (stream, _) = listener.accept().unwrap();
+
+        let request = read_request(&stream).unwrap();
+        assert_eq!(request.method(), "GET");
+        assert_eq!(request.uri().path(), "/aircraft");
+        assert_eq!(request.headers()["host"], "localhost");
+        let query = query(&request);
+        assert_eq!(query["bbox"], "5.5,47,15,55");
+        assert_eq!(query["track"], "true");
+    }
+
+    #[test]
+    fn test_read_incomplete_request() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        client.write_all(b"GET /aircraft HTTP/1.1\r\n").unwrap();
+        client.shutdown(std::net::Shutdown::Write).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+
+        assert!(read_request(&stream).is_none());
+    }
+}
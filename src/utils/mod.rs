@@ -0,0 +1,5 @@
+pub mod broadcast;
+pub mod circling;
+pub mod geo;
+pub mod http;
+pub mod icao;